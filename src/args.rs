@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 use clap::{Arg, Command};
-use crate::config::Args;
+use crate::config::{Args, OutputFormat};
 use crate::io::print_error;
 
 /// parse command line arguments
@@ -19,12 +19,18 @@ pub fn parse_args() -> Args {
         )
         .arg(
             Arg::new("target")
-                .short('t') 
+                .short('t')
                 .long("target")
                 .value_parser(clap::value_parser!(String))
-                .required(true)
                 .value_name("TARGET_DIR")
-                .help("Specifies the target directory where files will be copied"),
+                .help("Specifies the target directory where files will be copied (required unless using `serve`)"),
+        )
+        .arg(
+            Arg::new("base-url")
+                .long("base-url")
+                .value_parser(clap::value_parser!(String))
+                .value_name("URL")
+                .help("Absolute base URL (e.g. https://example.com) prepended to canonical links and sitemap.xml entries"),
         )
         .arg(
             Arg::new("verbose")
@@ -33,6 +39,121 @@ pub fn parse_args() -> Args {
                 .action(clap::ArgAction::SetTrue)
                 .help("Enables verbose output"),
         )
+        .arg(
+            Arg::new("search-index")
+                .long("search-index")
+                .action(clap::ArgAction::SetTrue)
+                .help("Emit a search_index.json for client-side search"),
+        )
+        .arg(
+            Arg::new("search-index-max-len")
+                .long("search-index-max-len")
+                .value_parser(clap::value_parser!(usize))
+                .value_name("CHARS")
+                .help("Caps the indexed plain-text body length per page"),
+        )
+        .arg(
+            Arg::new("highlight")
+                .long("highlight")
+                .action(clap::ArgAction::SetTrue)
+                .help("Enables server-side syntax highlighting of fenced code blocks"),
+        )
+        .arg(
+            Arg::new("highlight-theme")
+                .long("highlight-theme")
+                .value_parser(clap::value_parser!(String))
+                .value_name("THEME")
+                .help("Names the syntect theme used for syntax highlighting, or \"css\" to emit class names plus a companion stylesheet"),
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .action(clap::ArgAction::SetTrue)
+                .help("Watches the source directory and rebuilds on change"),
+        )
+        .arg(
+            Arg::new("minify")
+                .long("minify")
+                .action(clap::ArgAction::SetTrue)
+                .help("Minifies the final rendered HTML page"),
+        )
+        .arg(
+            Arg::new("math")
+                .long("math")
+                .action(clap::ArgAction::SetTrue)
+                .help("Renders $...$/$$...$$ spans and ```math blocks as KaTeX-ready markup"),
+        )
+        .arg(
+            Arg::new("mermaid")
+                .long("mermaid")
+                .action(clap::ArgAction::SetTrue)
+                .help("Renders ```mermaid fenced blocks as Mermaid diagram containers"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["html", "epub", "gemini", "gopher"])
+                .value_name("FORMAT")
+                .help("Output format: \"html\" (default), \"epub\", \"gemini\" (gemtext), or \"gopher\" (gophermap)"),
+        )
+        .arg(
+            Arg::new("mmdc-path")
+                .long("mmdc-path")
+                .value_parser(clap::value_parser!(String))
+                .value_name("PATH")
+                .help("Path to the mermaid-cli (mmdc) binary, for pages with mermaid_mode = \"prerendered\""),
+        )
+        .arg(
+            Arg::new("check-links")
+                .long("check-links")
+                .action(clap::ArgAction::SetTrue)
+                .help("Issues HEAD requests to validate external links (local links and intra-doc anchors are always checked)"),
+        )
+        .arg(
+            Arg::new("link-check-timeout")
+                .long("link-check-timeout")
+                .value_parser(clap::value_parser!(u64))
+                .value_name("SECS")
+                .help("Timeout in seconds for each external link check (default: 5)"),
+        )
+        .arg(
+            Arg::new("link-check-skip-domains")
+                .long("link-check-skip-domains")
+                .value_parser(clap::value_parser!(String))
+                .value_name("DOMAINS")
+                .help("Comma-separated list of domains to skip during external link checking"),
+        )
+        .arg(
+            Arg::new("backlinks")
+                .long("backlinks")
+                .action(clap::ArgAction::SetTrue)
+                .help("Renders a \"what links here\" nav on each page via the {{ backlinks_html }} placeholder"),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Serves the site from memory with live reload, without writing to disk")
+                .arg(
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .value_parser(clap::value_parser!(u16))
+                        .value_name("PORT")
+                        .help("TCP port to listen on (default: 8080)"),
+                ),
+        )
+        .subcommand(
+            Command::new("preview")
+                .about("Builds the site to disk, serves the target directory, and rebuilds on source changes")
+                .arg(
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .value_parser(clap::value_parser!(u16))
+                        .value_name("PORT")
+                        .help("TCP port to listen on (default: automatically picked)"),
+                ),
+        )
         .get_matches();
 
     let source_dir_str = matches
@@ -47,11 +168,62 @@ pub fn parse_args() -> Args {
                 })
         });
 
-    let target_dir_str = matches.get_one::<String>("target").unwrap();
+    let serve_port = matches
+        .subcommand_matches("serve")
+        .map(|serve_matches| *serve_matches.get_one::<u16>("port").unwrap_or(&8080));
+
+    let preview_port = matches
+        .subcommand_matches("preview")
+        .map(|preview_matches| *preview_matches.get_one::<u16>("port").unwrap_or(&0));
+
+    let target_dir_str = matches.get_one::<String>("target").cloned();
+    if serve_port.is_none() && target_dir_str.is_none() {
+        print_error("The --target/-t option is required unless using the `serve` subcommand.");
+        std::process::exit(1);
+    }
 
     Args {
         source: PathBuf::from(source_dir_str),
-        target: PathBuf::from(target_dir_str),
+        target: PathBuf::from(target_dir_str.unwrap_or_default()),
         verbose: *matches.get_one::<bool>("verbose").unwrap_or(&false),
+        base_url: matches
+            .get_one::<String>("base-url")
+            .cloned()
+            .unwrap_or_default(),
+        enable_search_index: *matches.get_one::<bool>("search-index").unwrap_or(&false),
+        search_index_max_body_len: matches.get_one::<usize>("search-index-max-len").copied(),
+        enable_syntax_highlighting: *matches.get_one::<bool>("highlight").unwrap_or(&false),
+        highlight_theme: matches
+            .get_one::<String>("highlight-theme")
+            .cloned()
+            .unwrap_or_else(|| "InspiredGitHub".to_string()),
+        watch: *matches.get_one::<bool>("watch").unwrap_or(&false),
+        minify_html: *matches.get_one::<bool>("minify").unwrap_or(&false),
+        enable_math_rendering: *matches.get_one::<bool>("math").unwrap_or(&false),
+        enable_mermaid: *matches.get_one::<bool>("mermaid").unwrap_or(&false),
+        mermaid_renderer_path: matches.get_one::<String>("mmdc-path").cloned(),
+        check_external_links: *matches.get_one::<bool>("check-links").unwrap_or(&false),
+        link_check_timeout_secs: matches
+            .get_one::<u64>("link-check-timeout")
+            .copied()
+            .unwrap_or(5),
+        link_check_skip_domains: matches
+            .get_one::<String>("link-check-skip-domains")
+            .map(|s| {
+                s.split(',')
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        output_format: match matches.get_one::<String>("format").map(String::as_str) {
+            Some("epub") => OutputFormat::Epub,
+            Some("gemini") => OutputFormat::Gemini,
+            Some("gopher") => OutputFormat::Gopher,
+            _ => OutputFormat::Html,
+        },
+        serve_port,
+        enable_backlinks: *matches.get_one::<bool>("backlinks").unwrap_or(&false),
+        preview_port,
     }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, fs, path::Path, path::PathBuf};
+
+use regex::Regex;
+
+use crate::config::{Args, MetadataMap, SiteMap};
+use crate::html::generate_canonical_url;
+use crate::markdown::resolve_link_path;
+
+/// `target rel_path -> source rel_paths that link to it`, built once from
+/// every markdown file's raw `[text](target.md)` links before any page is
+/// rendered, so `build_final_html` can look up a page's inbound links by the
+/// time it formats that page.
+pub type BacklinksIndex = HashMap<PathBuf, Vec<PathBuf>>;
+
+pub fn build_backlinks_index(args: &Args, site_map: &SiteMap) -> BacklinksIndex {
+    let link_regex = Regex::new(r"\[[^\]]*\]\(([^)\s]+)(?:\s+[^)]*)?\)").unwrap();
+    let mut index: BacklinksIndex = HashMap::new();
+
+    let mut md_paths: Vec<_> = site_map
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    md_paths.sort();
+
+    for rel_path in md_paths {
+        let path_source = args.source.join(rel_path);
+        let Ok(content) = fs::read_to_string(&path_source) else {
+            continue;
+        };
+
+        for caps in link_regex.captures_iter(&content) {
+            let target = caps[1].trim();
+            if target.starts_with("http://") || target.starts_with("https://") || target.starts_with('#') {
+                continue;
+            }
+
+            let (file_part, _fragment) = target.split_once('#').unwrap_or((target, ""));
+            let Some(target_rel) = resolve_target_rel_path(rel_path, file_part, site_map) else {
+                continue;
+            };
+
+            if &target_rel == rel_path {
+                continue; // skip self-links
+            }
+
+            let sources = index.entry(target_rel).or_default();
+            if !sources.contains(rel_path) {
+                sources.push(rel_path.clone());
+            }
+        }
+    }
+
+    index
+}
+
+/// Resolves a raw markdown link target to the `rel_path` of the `.md` file
+/// it points at, applying the same `index.md` directory-link convention as
+/// `rewrite_link_to_relative`. Returns `None` for links that don't resolve
+/// to a known markdown page (assets, non-existent directories, etc.).
+fn resolve_target_rel_path(from_rel_path: &Path, file_part: &str, site_map: &SiteMap) -> Option<PathBuf> {
+    if file_part.is_empty() {
+        return None;
+    }
+
+    let resolved_abs = resolve_link_path(from_rel_path, Path::new(file_part));
+    let target_rel = resolved_abs
+        .strip_prefix("/")
+        .unwrap_or(&resolved_abs)
+        .to_path_buf();
+
+    if target_rel.extension().is_some_and(|ext| ext == "md") {
+        return site_map.contains(&target_rel).then_some(target_rel);
+    }
+
+    if target_rel.extension().is_none() {
+        let index_candidate = target_rel.join("index.md");
+        if site_map.contains(&index_candidate) {
+            return Some(index_candidate);
+        }
+    }
+
+    None
+}
+
+/// Renders the `<nav class="backlinks">` list for one page, or an empty
+/// string (never the raw `{{ backlinks_html }}` placeholder) when it has no
+/// inbound links.
+pub fn render_backlinks_html(
+    target_rel_path: &Path,
+    backlinks_index: &BacklinksIndex,
+    metadata_map: &MetadataMap,
+    base_url: &str,
+) -> String {
+    let Some(sources) = backlinks_index.get(target_rel_path) else {
+        return String::new();
+    };
+
+    if sources.is_empty() {
+        return String::new();
+    }
+
+    let mut sorted_sources = sources.clone();
+    sorted_sources.sort();
+
+    let mut html = String::from(r#"<nav class="backlinks"><h2>Backlinks</h2><ul>"#);
+    for source in &sorted_sources {
+        let title = metadata_map
+            .get(source)
+            .and_then(|m| m.nav_title.clone().or_else(|| m.computed_title.clone()))
+            .unwrap_or_else(|| source.to_string_lossy().to_string());
+        let url = generate_canonical_url(source, base_url);
+        html.push_str(&format!(r#"<li><a href="{}">{}</a></li>"#, url, title));
+    }
+    html.push_str("</ul></nav>");
+
+    html
+}
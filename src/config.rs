@@ -18,12 +18,55 @@ pub struct PageMetadata {
     pub sitemap_changefreq: Option<String>,
     pub sitemap_priority: Option<f32>,
 
+    // Opts a page out of the search_index.json built by `SearchIndexBuilder`
+    // (defaults to included, mirroring `include_in_sitemap`'s default-true shape)
+    pub include_in_search: Option<bool>,
+
     // NEW: Computed title (extracted from heading or metadata)
     pub computed_title: Option<String>,
+
+    // Per-page table of contents toggle (enabled by default)
+    pub disable_toc: Option<bool>,
+
+    // Taxonomy terms, e.g. { "tags": ["rust", "cli"], "categories": ["dev-tools"] }
+    pub taxonomies: Option<BTreeMap<String, Vec<String>>>,
+
+    // Shorthand for `taxonomies.tags`: both are folded into the same "tags"
+    // bucket of `TaxonomyIndex` by `build_taxonomy_index`.
+    pub tags: Option<Vec<String>>,
+
+    // Per-page toggles for the KaTeX/Mermaid client-side asset includes
+    pub disable_math: Option<bool>,
+    pub disable_mermaid: Option<bool>,
+
+    // "client" (default): wrap spans for the client-side KaTeX script in
+    // `KATEX_HEAD_INCLUDE`. "prerendered": render to static HTML/MathML at
+    // build time via the `katex` crate so the page ships without client JS.
+    pub math_mode: Option<String>,
+
+    // "client" (default): emit `<div class="mermaid">` for the Mermaid
+    // client script in `MERMAID_HEAD_INCLUDE`. "prerendered": shell out to
+    // `Args::mermaid_renderer_path` (`mmdc`) for script-free inline SVG.
+    pub mermaid_mode: Option<String>,
+
+    // Per-page syntax-highlighting overrides: opt a page out entirely, or
+    // pick a syntect theme different from the site-wide `--highlight-theme`
+    pub disable_highlighting: Option<bool>,
+    pub highlight_theme: Option<String>,
 }
 
 pub type MetadataMap = BTreeMap<PathBuf, PageMetadata>;
 
+/// Selects which renderer `main` dispatches to; `Epub` bypasses the
+/// HTML-site pipeline (nav, taxonomy, search index) entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Html,
+    Epub,
+    Gemini,
+    Gopher,
+}
+
 // An element to appear in site navigation
 #[derive(Debug, Clone)]
 pub enum NavItem {
@@ -55,11 +98,28 @@ impl NavItem {
 }
 
 // Parsed from command line arguments
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Args {
     pub source: PathBuf,
     pub target: PathBuf,
     pub verbose: bool,
+    pub base_url: String,
+    pub enable_search_index: bool,
+    pub search_index_max_body_len: Option<usize>,
+    pub enable_syntax_highlighting: bool,
+    pub highlight_theme: String,
+    pub watch: bool,
+    pub minify_html: bool,
+    pub enable_math_rendering: bool,
+    pub enable_mermaid: bool,
+    pub mermaid_renderer_path: Option<String>,
+    pub check_external_links: bool,
+    pub link_check_timeout_secs: u64,
+    pub link_check_skip_domains: Vec<String>,
+    pub output_format: OutputFormat,
+    pub serve_port: Option<u16>,
+    pub enable_backlinks: bool,
+    pub preview_port: Option<u16>,
 }
 
 pub type NavTree = BTreeMap<String, NavItem>;
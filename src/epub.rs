@@ -0,0 +1,297 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::io::Write as _;
+use std::{fs, io, path::Path};
+
+use pulldown_cmark::{Options, Parser};
+use regex::Regex;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::config::{Args, MetadataMap, SiteMap};
+use crate::io::print_info;
+use crate::markdown::{normalize_markdown_content, prepare_content_for_parser, process_markdown_events};
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+/// Renders the whole site as a single EPUB 3 document instead of the usual
+/// HTML tree: walks `SiteMap` in sort order, runs each page through the same
+/// `process_markdown_events` pipeline the HTML renderer uses, and copies any
+/// locally-referenced images into the book's `OEBPS/assets` directory. This
+/// bypasses nav/taxonomy/search-index generation entirely, mirroring how
+/// mdbook-epub treats EPUB as an alternate renderer rather than a post-pass
+/// over the generated site.
+pub fn generate_epub(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    _html_template: &str,
+) -> io::Result<()> {
+    let mut md_paths: Vec<_> = site_map
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    md_paths.sort();
+
+    let epub_path = args.target.join("book.epub");
+    let mut zip = ZipWriter::new(fs::File::create(&epub_path)?);
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"application/epub+zip")?;
+
+    zip.start_file("META-INF/container.xml", deflated)?;
+    zip.write_all(CONTAINER_XML.as_bytes())?;
+
+    let img_src_regex = Regex::new(r#"src="([^"]+)""#).unwrap();
+    let link_href_regex = Regex::new(r#"href="([^"]+)""#).unwrap();
+
+    // `process_markdown_events` already rewrote internal `.md` links to
+    // site-relative `.html` paths (same as the HTML renderer), but the EPUB
+    // ships every chapter flattened into `OEBPS/chap{N}.xhtml` — those paths
+    // no longer resolve. Map each source page to its chapter file up front
+    // (mirroring `src/gemtext.rs`'s `.html` -> `.gmi` suffix rewrite) so
+    // `rewrite_internal_links` can retarget every `href` below.
+    let chapter_map: std::collections::HashMap<&Path, String> = md_paths
+        .iter()
+        .enumerate()
+        .map(|(index, rel_path)| (rel_path.as_path(), format!("chap{}.xhtml", index)))
+        .collect();
+
+    let mut manifest_items = String::new();
+    let mut spine_items = String::new();
+    let mut nav_items = String::new();
+    let mut copied_assets = HashSet::new();
+
+    for (index, rel_path) in md_paths.iter().enumerate() {
+        let path_source = args.source.join(rel_path);
+        let markdown_input = fs::read_to_string(&path_source)?;
+        let (normalized_content, _) = normalize_markdown_content(&markdown_input, &path_source);
+
+        let default_metadata = crate::config::PageMetadata::default();
+        let metadata = metadata_map.get(*rel_path).unwrap_or(&default_metadata);
+        let content_for_parser = prepare_content_for_parser(&normalized_content, metadata);
+
+        let parser = Parser::new_ext(&content_for_parser, Options::empty());
+        let (content_html, title, _toc_html, _uses_math, _uses_mermaid) =
+            process_markdown_events(args, site_map, metadata_map, parser, rel_path);
+
+        let content_html = rewrite_and_copy_assets(
+            &content_html,
+            &img_src_regex,
+            &path_source,
+            &mut zip,
+            &deflated,
+            &mut copied_assets,
+        )?;
+
+        let content_html =
+            rewrite_internal_links(&content_html, &link_href_regex, rel_path, &chapter_map);
+
+        let chapter_id = format!("chap{}", index);
+        let chapter_file = format!("{}.xhtml", chapter_id);
+
+        let xhtml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\"><head><title>{}</title></head><body>{}</body></html>",
+            escape_xml(&title),
+            content_html
+        );
+
+        zip.start_file(format!("OEBPS/{}", chapter_file), deflated)?;
+        zip.write_all(xhtml.as_bytes())?;
+
+        manifest_items.push_str(&format!(
+            r#"<item id="{id}" href="{file}" media-type="application/xhtml+xml"/>"#,
+            id = chapter_id,
+            file = chapter_file
+        ));
+        spine_items.push_str(&format!(r#"<itemref idref="{}"/>"#, chapter_id));
+        nav_items.push_str(&format!(
+            r#"<li><a href="{file}">{title}</a></li>"#,
+            file = chapter_file,
+            title = escape_xml(&title)
+        ));
+    }
+
+    let nav_xhtml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\"><head><title>Table of Contents</title></head><body><nav epub:type=\"toc\"><ol>{}</ol></nav></body></html>",
+        nav_items
+    );
+    zip.start_file("OEBPS/nav.xhtml", deflated)?;
+    zip.write_all(nav_xhtml.as_bytes())?;
+    manifest_items.push_str(
+        r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>"#,
+    );
+
+    let book_id = book_identifier(&args.source);
+    let content_opf = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">urn:uuid:{book_id}</dc:identifier>
+    <dc:title>Generated Site</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>{manifest_items}</manifest>
+  <spine>{spine_items}</spine>
+</package>"#
+    );
+
+    zip.start_file("OEBPS/content.opf", deflated)?;
+    zip.write_all(content_opf.as_bytes())?;
+
+    zip.finish()?;
+
+    if args.verbose {
+        print_info(&format!("Generated EPUB: {}", epub_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Finds `src="..."` references to local image files, copies each referenced
+/// file into `OEBPS/assets/<flattened-name>` exactly once, and rewrites the
+/// `src` attribute to the in-book path.
+fn rewrite_and_copy_assets(
+    content_html: &str,
+    img_src_regex: &Regex,
+    path_source: &Path,
+    zip: &mut ZipWriter<fs::File>,
+    options: &FileOptions,
+    copied_assets: &mut HashSet<String>,
+) -> io::Result<String> {
+    let parent_dir = path_source.parent().unwrap_or_else(|| Path::new(""));
+    let mut rewritten = content_html.to_string();
+
+    for caps in img_src_regex.captures_iter(content_html) {
+        let src = &caps[1];
+        if src.starts_with("http://") || src.starts_with("https://") || src.starts_with('/') {
+            continue;
+        }
+
+        let asset_path = parent_dir.join(src);
+        if !asset_path.exists() {
+            continue;
+        }
+
+        let flattened_name = src.replace(['/', '\\'], "_");
+        let in_book_path = format!("assets/{}", flattened_name);
+
+        if copied_assets.insert(flattened_name.clone()) {
+            let bytes = fs::read(&asset_path)?;
+            zip.start_file(format!("OEBPS/{}", in_book_path), *options)?;
+            zip.write_all(&bytes)?;
+        }
+
+        rewritten = rewritten.replace(
+            &format!(r#"src="{}""#, src),
+            &format!(r#"src="{}""#, in_book_path),
+        );
+    }
+
+    Ok(rewritten)
+}
+
+/// Retargets every local `href="...\.html"` produced by `process_markdown_events`
+/// (site-relative to `rel_path`'s directory, same as the HTML renderer emits)
+/// to the flat `chapN.xhtml` name the linked page actually ended up with in
+/// the EPUB. External links, mailto links and in-page `#anchor`s are left
+/// alone; a link that doesn't resolve to a known chapter (e.g. the source
+/// `.md` no longer exists) is also left alone rather than guessed at.
+fn rewrite_internal_links(
+    content_html: &str,
+    link_href_regex: &Regex,
+    rel_path: &Path,
+    chapter_map: &std::collections::HashMap<&Path, String>,
+) -> String {
+    let current_dir = rel_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut rewritten = content_html.to_string();
+
+    for caps in link_href_regex.captures_iter(content_html) {
+        let href = &caps[1];
+        if href.starts_with("http://")
+            || href.starts_with("https://")
+            || href.starts_with("mailto:")
+            || href.starts_with('#')
+        {
+            continue;
+        }
+
+        let (path_part, fragment) = match href.split_once('#') {
+            Some((p, f)) => (p, Some(f)),
+            None => (href, None),
+        };
+        if !path_part.ends_with(".html") {
+            continue;
+        }
+
+        let joined = current_dir.join(path_part);
+        let normalized = normalize_path(&joined);
+
+        let md_path = if normalized.file_name().is_some_and(|n| n == "index.html") {
+            normalized.with_file_name("index.md")
+        } else {
+            normalized.with_extension("md")
+        };
+
+        if let Some(chapter_file) = chapter_map.get(md_path.as_path()) {
+            let new_href = match fragment {
+                Some(f) => format!("{}#{}", chapter_file, f),
+                None => chapter_file.clone(),
+            };
+            rewritten = rewritten.replace(
+                &format!(r#"href="{}""#, href),
+                &format!(r#"href="{}""#, new_href),
+            );
+        }
+    }
+
+    rewritten
+}
+
+/// Resolves `..`/`.` components in a joined relative path without touching
+/// the filesystem (the target may not exist as a real file on disk, e.g.
+/// during this link-retargeting pass it's looked up in `chapter_map` instead).
+fn normalize_path(path: &Path) -> std::path::PathBuf {
+    let mut out = std::path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Derives a stable (not globally unique, but build-to-build stable) UUID-shaped
+/// identifier from the source directory so repeated builds of the same site
+/// don't change `dc:identifier` on every run.
+fn book_identifier(source_dir: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    source_dir.hash(&mut hasher);
+    let hash = hasher.finish();
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        (hash >> 32) as u32,
+        (hash >> 16) as u16 & 0xffff,
+        hash as u16,
+        (hash >> 48) as u16,
+        hash & 0xffff_ffff_ffff
+    )
+}
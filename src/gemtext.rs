@@ -0,0 +1,216 @@
+use std::{fs, io, path::Path};
+
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+
+use crate::config::{Args, MetadataMap, PageMetadata, SiteMap};
+use crate::io::print_info;
+use crate::markdown::{
+    extract_plain_text, normalize_markdown_content, prepare_content_for_parser,
+    rewrite_link_to_relative,
+};
+
+/// Renders the whole site as gemtext (`.gmi`) files mirroring the source
+/// tree, the way a blog archiver publishes `gemini.txt` alongside HTML.
+pub fn generate_gemini_site(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+) -> io::Result<()> {
+    for rel_path in sorted_markdown_paths(site_map) {
+        let path_source = args.source.join(rel_path);
+        let path_target = args.target.join(rel_path).with_extension("gmi");
+
+        if let Some(parent) = path_target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let markdown_input = fs::read_to_string(&path_source)?;
+        let (normalized_content, _) = normalize_markdown_content(&markdown_input, &path_source);
+        let default_metadata = PageMetadata::default();
+        let metadata = metadata_map.get(rel_path).unwrap_or(&default_metadata);
+        let content_for_parser = prepare_content_for_parser(&normalized_content, metadata);
+
+        let gemtext = events_to_gemtext(&content_for_parser, rel_path, site_map);
+        fs::write(&path_target, gemtext)?;
+
+        if args.verbose {
+            print_info(&format!(
+                "Converted (gemtext): {} -> {}",
+                rel_path.display(),
+                path_target.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the site as a Gopher menu: one `gophermap` index at the site
+/// root listing every page as a type-`0` (text file) item, plus one `.txt`
+/// file per page holding its plain-text body.
+pub fn generate_gopher_site(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+) -> io::Result<()> {
+    let mut gophermap = String::new();
+
+    for rel_path in sorted_markdown_paths(site_map) {
+        let path_source = args.source.join(rel_path);
+        let path_target = args.target.join(rel_path).with_extension("txt");
+
+        if let Some(parent) = path_target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let markdown_input = fs::read_to_string(&path_source)?;
+        let (normalized_content, _) = normalize_markdown_content(&markdown_input, &path_source);
+        let plain_text = extract_plain_text(&normalized_content);
+        fs::write(&path_target, &plain_text)?;
+
+        let title = metadata_map
+            .get(rel_path)
+            .and_then(|m| m.nav_title.clone().or_else(|| m.computed_title.clone()))
+            .unwrap_or_else(|| rel_path.to_string_lossy().to_string());
+
+        let selector = format!("/{}", rel_path.with_extension("txt").display());
+        gophermap.push_str(&format!("0{}\t{}\tlocalhost\t70\r\n", title, selector));
+
+        if args.verbose {
+            print_info(&format!(
+                "Converted (gopher): {} -> {}",
+                rel_path.display(),
+                path_target.display()
+            ));
+        }
+    }
+
+    gophermap.push_str(".\r\n");
+    fs::write(args.target.join("gophermap"), gophermap)?;
+
+    Ok(())
+}
+
+fn sorted_markdown_paths(site_map: &SiteMap) -> Vec<&std::path::PathBuf> {
+    let mut paths: Vec<_> = site_map
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Converts a parsed markdown event stream to gemtext: headings become
+/// `#`/`##`/`###` lines, list items become `* ` lines, fenced code blocks
+/// keep their ` ``` ` fences, and every link is hoisted onto its own
+/// `=> url text` line after the paragraph that contains it, since Gemini
+/// forbids inline links. Local `.md` targets are rewritten to `.gmi` via
+/// the same [`rewrite_link_to_relative`] the HTML renderer uses.
+fn events_to_gemtext(content: &str, path_rel: &Path, site_map: &SiteMap) -> String {
+    let parser = Parser::new(content);
+    let mut out = String::new();
+    let mut para_buf = String::new();
+    let mut pending_links: Vec<String> = Vec::new();
+    let mut in_code_block = false;
+    let mut link_text_start = 0usize;
+    let mut link_dest = String::new();
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Heading(level, ..)) => {
+                flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+                let prefix = match level {
+                    HeadingLevel::H1 => "#",
+                    HeadingLevel::H2 => "##",
+                    _ => "###",
+                };
+                para_buf.push_str(prefix);
+                para_buf.push(' ');
+            }
+            Event::End(Tag::Heading(..)) => {
+                flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+                in_code_block = true;
+                out.push_str("```\n");
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+                out.push_str("```\n\n");
+            }
+            Event::Start(Tag::Item) => {
+                para_buf.push_str("* ");
+            }
+            Event::End(Tag::Item) => {
+                flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+            }
+            Event::End(Tag::Paragraph) => {
+                flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+            }
+            Event::Start(Tag::Link(_, dest, _)) => {
+                link_text_start = para_buf.len();
+                link_dest = dest.to_string();
+            }
+            Event::End(Tag::Link(..)) => {
+                let link_text = para_buf[link_text_start..].trim().to_string();
+
+                let gemini_dest = if link_dest.starts_with("http://")
+                    || link_dest.starts_with("https://")
+                {
+                    link_dest.clone()
+                } else {
+                    let mut rewritten = rewrite_link_to_relative(
+                        path_rel,
+                        Path::new(&link_dest),
+                        site_map,
+                        false,
+                    );
+                    if rewritten.ends_with(".html") {
+                        rewritten.truncate(rewritten.len() - ".html".len());
+                        rewritten.push_str(".gmi");
+                    }
+                    rewritten
+                };
+
+                let link_line = if link_text.is_empty() {
+                    format!("=> {}", gemini_dest)
+                } else {
+                    format!("=> {} {}", gemini_dest, link_text)
+                };
+                pending_links.push(link_line);
+            }
+            Event::Text(text) => {
+                if in_code_block {
+                    out.push_str(&text);
+                } else {
+                    para_buf.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => para_buf.push(' '),
+            _ => {}
+        }
+    }
+
+    flush_paragraph(&mut out, &mut para_buf, &mut pending_links);
+    out
+}
+
+fn flush_paragraph(out: &mut String, para_buf: &mut String, pending_links: &mut Vec<String>) {
+    let trimmed = para_buf.trim();
+    if !trimmed.is_empty() {
+        out.push_str(trimmed);
+        out.push('\n');
+    }
+
+    for link in pending_links.drain(..) {
+        out.push_str(&link);
+        out.push('\n');
+    }
+
+    if !trimmed.is_empty() {
+        out.push('\n');
+    }
+
+    para_buf.clear();
+}
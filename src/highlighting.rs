@@ -0,0 +1,139 @@
+use std::{fs, io, sync::OnceLock};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{
+    css_for_theme_with_class_style, styled_line_to_highlighted_html, ClassStyle,
+    ClassedHTMLGenerator, IncludeBackground,
+};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+use crate::config::Args;
+use crate::io::{print_error, print_info, print_warning};
+
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+
+/// Special `highlight_theme` value meaning "emit `syntect`'s class names
+/// instead of inline styles, and ship a companion stylesheet" so a theme can
+/// be swapped by dropping in different CSS, without rebuilding the site.
+pub const CSS_CLASSES_THEME: &str = "css";
+
+fn syntax_set() -> &'static SyntaxSet {
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Confirms `theme_name` is either the special `"css"` mode or a real
+/// bundled `syntect` theme, exiting the process via `print_error` otherwise.
+pub fn validate_highlight_theme(theme_name: &str) {
+    if theme_name == CSS_CLASSES_THEME || theme_set().themes.contains_key(theme_name) {
+        return;
+    }
+
+    print_error(&format!(
+        "Unknown syntax highlighting theme '{}'. Pass a bundled syntect theme name or \"css\".",
+        theme_name
+    ));
+    std::process::exit(1);
+}
+
+/// Highlights one fenced code block's raw source with `syntect`, falling
+/// back to escaped plaintext (mirroring Zola's `highlighting` module) when
+/// the language token isn't recognized, and to the `InspiredGitHub` theme
+/// (with a warning) when the theme isn't.
+pub fn highlight_code_block(code: &str, lang: &str, theme_name: &str) -> String {
+    let syntax_set = syntax_set();
+
+    let Some(syntax) = syntax_set.find_syntax_by_token(lang) else {
+        return escape_plain_code_block(code);
+    };
+
+    if theme_name == CSS_CLASSES_THEME {
+        return highlight_code_block_as_classes(code, syntax, syntax_set);
+    }
+
+    let theme_set = theme_set();
+    let theme = theme_set.themes.get(theme_name).unwrap_or_else(|| {
+        print_warning(&format!(
+            "Unknown syntax highlighting theme '{}', falling back to 'InspiredGitHub'",
+            theme_name
+        ));
+        &theme_set.themes["InspiredGitHub"]
+    });
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut highlighted_lines = String::new();
+
+    for line in LinesWithEndings::from(code) {
+        let ranges = match highlighter.highlight_line(line, syntax_set) {
+            Ok(ranges) => ranges,
+            Err(_) => return escape_plain_code_block(code),
+        };
+
+        match styled_line_to_highlighted_html(&ranges[..], IncludeBackground::No) {
+            Ok(html) => highlighted_lines.push_str(&html),
+            Err(_) => return escape_plain_code_block(code),
+        }
+    }
+
+    format!("<pre class=\"highlight\"><code>{}</code></pre>", highlighted_lines)
+}
+
+/// `"css"` theme mode: emit syntect's scope class names (via `ClassStyle::Spaced`,
+/// e.g. `source rust`)
+/// instead of baking one theme's colors into every page as inline styles.
+fn highlight_code_block_as_classes(
+    code: &str,
+    syntax: &SyntaxReference,
+    syntax_set: &SyntaxSet,
+) -> String {
+    let mut generator =
+        ClassedHTMLGenerator::new_with_class_style(syntax, syntax_set, ClassStyle::Spaced);
+
+    for line in LinesWithEndings::from(code) {
+        if generator
+            .parse_html_for_line_which_includes_newline(line)
+            .is_err()
+        {
+            return escape_plain_code_block(code);
+        }
+    }
+
+    format!(
+        "<pre class=\"highlight\"><code>{}</code></pre>",
+        generator.finalize()
+    )
+}
+
+/// Renders the companion stylesheet for `highlight_theme = "css"` mode.
+/// Always styled after `InspiredGitHub`; swap themes post-build by replacing
+/// this file, since the HTML itself only carries class names.
+fn css_theme_stylesheet() -> String {
+    let theme = &theme_set().themes["InspiredGitHub"];
+    css_for_theme_with_class_style(theme, ClassStyle::Spaced).unwrap_or_default()
+}
+
+/// Writes the `highlight_theme = "css"` companion stylesheet to the target
+/// root, so classed code blocks have something to reference by default.
+pub fn write_css_theme_stylesheet(args: &Args) -> io::Result<()> {
+    let path = args.target.join("syntax-theme.css");
+    fs::write(&path, css_theme_stylesheet())?;
+
+    if args.verbose {
+        print_info(&format!("Wrote syntax highlighting stylesheet to: {}", path.display()));
+    }
+
+    Ok(())
+}
+
+fn escape_plain_code_block(code: &str) -> String {
+    let escaped = code
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!("<pre><code>{}</code></pre>", escaped)
+}
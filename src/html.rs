@@ -5,6 +5,22 @@ use crate::config::{Args, MetadataMap};
 use crate::io::{print_info, print_warning};
 use crate::processing::get_last_modified_date;
 
+// Conditional <head> includes: only spliced in for pages that actually use
+// math/diagrams, so most pages don't pay for the extra client-side assets.
+pub const KATEX_HEAD_INCLUDE: &str = concat!(
+    "<link rel=\"stylesheet\" href=\"https://cdn.jsdelivr.net/npm/katex@0.16.9/dist/katex.min.css\">\n",
+    "<script defer src=\"https://cdn.jsdelivr.net/npm/katex@0.16.9/dist/katex.min.js\" ",
+    "onload=\"document.querySelectorAll('.math').forEach(function (el) { ",
+    "katex.render(el.textContent, el, {displayMode: el.classList.contains('display'), throwOnError: false}); ",
+    "});\"></script>",
+);
+
+pub const MERMAID_HEAD_INCLUDE: &str = concat!(
+    "<script src=\"https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.min.js\"></script>\n",
+    "<script>mermaid.initialize({ startOnLoad: true });</script>",
+);
+
+#[allow(clippy::too_many_arguments)]
 pub fn format_html_page(
     title: &str,
     rel_path_str: &str,
@@ -12,6 +28,11 @@ pub fn format_html_page(
     last_modified_time: &str,
     nav_html: &str,
     content: &str,
+    toc_html: &str,
+    backlinks_html: &str,
+    prev_next_html: &str,
+    math_assets_html: &str,
+    mermaid_assets_html: &str,
     html_template: &str,
     breadcrumb_html: &str,
     canonical_url: &str,
@@ -24,6 +45,11 @@ pub fn format_html_page(
         .replace("{{ last_modified }}", last_modified_time)
         .replace("{{ nav_html }}", nav_html)
         .replace("{{ content }}", content)
+        .replace("{{ toc_html }}", toc_html)
+        .replace("{{ backlinks_html }}", backlinks_html)
+        .replace("{{ prev_next_html }}", prev_next_html)
+        .replace("{{ math_assets_html }}", math_assets_html)
+        .replace("{{ mermaid_assets_html }}", mermaid_assets_html)
         .replace("{{ breadcrumb_html }}", breadcrumb_html)
         .replace("{{ canonical_url }}", canonical_url)
 }
@@ -158,42 +184,95 @@ pub fn generate_canonical_url(rel_path: &Path, base_url: &str) -> String {
     }
 }
 
+/// What `convert_urls_to_anchors` found and where, before either is spliced
+/// in as an `<a>` tag.
+enum AutolinkMatch {
+    Url(usize, usize),
+    Email(usize, usize),
+}
+
+impl AutolinkMatch {
+    fn start(&self) -> usize {
+        match self {
+            AutolinkMatch::Url(start, _) | AutolinkMatch::Email(start, _) => *start,
+        }
+    }
+
+    fn end(&self) -> usize {
+        match self {
+            AutolinkMatch::Url(_, end) | AutolinkMatch::Email(_, end) => *end,
+        }
+    }
+}
+
 pub fn convert_urls_to_anchors(html: &str) -> String {
     let url_regex = Regex::new(r"https?://[^\s<]+").unwrap();
+    let email_regex = Regex::new(
+        r"[A-Za-z0-9.!#$%&'*+/=?^_`{|}~-]+@[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?(?:\.[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?)+",
+    )
+    .unwrap();
     let anchor_regex = Regex::new(r"<a\b[^>]*>.*?</a>").unwrap();
 
+    let anchor_ranges: Vec<(usize, usize)> = anchor_regex
+        .find_iter(html)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+
+    let url_ranges: Vec<(usize, usize)> = url_regex
+        .find_iter(html)
+        .map(|m| trim_trailing_punctuation(html, m.start(), m.end()))
+        .collect();
+
+    let mut matches: Vec<AutolinkMatch> = url_ranges
+        .iter()
+        .map(|&(start, end)| AutolinkMatch::Url(start, end))
+        .collect();
+
+    // Basic-auth URLs like `https://user@host/` contain an `@`, so drop any
+    // email match that's really just a substring of a URL we already found.
+    matches.extend(email_regex.find_iter(html).filter_map(|m| {
+        let is_inside_url = url_ranges
+            .iter()
+            .any(|&(u_start, u_end)| m.start() >= u_start && m.end() <= u_end);
+        (!is_inside_url).then(|| AutolinkMatch::Email(m.start(), m.end()))
+    }));
+
+    matches.sort_by_key(AutolinkMatch::start);
+
     let mut result = String::new();
     let mut last_pos = 0;
 
-    let mut anchor_ranges = Vec::new();
-    for mat in anchor_regex.find_iter(html) {
-        anchor_ranges.push((mat.start(), mat.end()));
-    }
+    for autolink_match in matches {
+        let start = autolink_match.start();
+        let end = autolink_match.end();
 
-    for url_match in url_regex.find_iter(html) {
-        let start = url_match.start();
-        let end = url_match.end();
+        if start < last_pos {
+            continue; // overlaps a match already emitted
+        }
 
         let in_anchor = anchor_ranges
             .iter()
             .any(|(a_start, a_end)| start >= *a_start && end <= *a_end);
+        if in_anchor {
+            continue;
+        }
 
-        if !in_anchor {
-            result.push_str(&html[last_pos..start]);
+        result.push_str(&html[last_pos..start]);
+        let text = &html[start..end];
 
-            let url = url_match.as_str();
-            let is_external = url.starts_with("http://") || url.starts_with("https://");
-            if is_external {
+        match autolink_match {
+            AutolinkMatch::Url(..) => {
                 result.push_str(&format!(
                     "<a href=\"{}\" target=\"_blank\" rel=\"noopener noreferrer\">{}</a>",
-                    url, url
+                    text, text
                 ));
-            } else {
-                result.push_str(&format!("<a href=\"{}\">{}</a>", url, url));
             }
-
-            last_pos = end;
+            AutolinkMatch::Email(..) => {
+                result.push_str(&format!("<a href=\"mailto:{}\">{}</a>", text, text));
+            }
         }
+
+        last_pos = end;
     }
 
     result.push_str(&html[last_pos..]);
@@ -205,37 +284,201 @@ pub fn convert_urls_to_anchors(html: &str) -> String {
     }
 }
 
-pub fn generate_sitemap_xml(args: &Args, metadata_map: &MetadataMap) -> io::Result<()> {
-    let sitemap_path = args.target.join("sitemap.xml");
-
-    let default_changefreq = "monthly";
-    let base_priority = 0.5;
+/// Trims trailing characters from a raw URL match that are almost never
+/// actually part of the URL: sentence punctuation, and a closing
+/// `)`/`]`/`}` that has no matching opener inside the match (so "(see
+/// https://example.com)" doesn't swallow the closing paren).
+fn trim_trailing_punctuation(html: &str, start: usize, mut end: usize) -> (usize, usize) {
+    loop {
+        let Some(ch) = html[start..end].chars().next_back() else {
+            break;
+        };
+
+        let should_trim = match ch {
+            '.' | ',' | ';' | ':' | '!' | '?' => true,
+            ')' | ']' | '}' => {
+                let opener = match ch {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                html[start..end].matches(ch).count() > html[start..end].matches(opener).count()
+            }
+            _ => false,
+        };
 
-    let mut entries = Vec::new();
+        if !should_trim {
+            break;
+        }
+        end -= ch.len_utf8();
+    }
 
-    for (rel_path, metadata) in metadata_map.iter() {
-        if metadata.include_in_sitemap.unwrap_or(false) {
-            let mut url_path = rel_path.to_path_buf();
+    (start, end)
+}
 
-            if rel_path.file_name().is_some_and(|n| n == "index.md") {
-                if rel_path.parent().is_some_and(|p| p.as_os_str().is_empty()) {
-                    url_path = std::path::PathBuf::from("");
-                } else {
-                    url_path = rel_path.parent().unwrap().to_path_buf();
+/// Collapses insignificant inter-tag whitespace, strips HTML comments
+/// (except conditional ones, e.g. `<!--[if IE]>...<![endif]-->`), and drops
+/// redundant attribute quoting, leaving `<pre>`/`<code>`/`<textarea>`/
+/// `<script>`/`<style>` contents byte-for-byte intact so highlighted code,
+/// preformatted blocks, and CSS aren't corrupted.
+pub fn minify_html(html: &str) -> String {
+    const PRESERVE_TAGS: [&str; 5] = ["pre", "code", "textarea", "script", "style"];
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut preserve_stack: Vec<String> = Vec::new();
+    let mut pending_space = false;
+
+    while !rest.is_empty() {
+        if let Some(after_open) = rest.strip_prefix("<!--").filter(|_| preserve_stack.is_empty()) {
+            if let Some(end) = after_open.find("-->") {
+                let comment_end = end + "-->".len();
+                let comment = &after_open[..comment_end];
+                // Conditional comments (`<!--[if IE]>...<![endif]-->`) gate
+                // browser-specific markup, so stripping them like ordinary
+                // comments would change what old IE actually renders.
+                if comment.starts_with("[if") {
+                    output.push_str("<!--");
+                    output.push_str(comment);
                 }
+                rest = &after_open[comment_end..];
+                continue;
+            } else {
+                break;
+            }
+        }
+
+        if rest.starts_with('<') {
+            let tag_end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+            let tag = &rest[..tag_end];
+
+            if pending_space {
+                output.push(' ');
+                pending_space = false;
+            }
+            if preserve_stack.is_empty() {
+                output.push_str(&unquote_attributes(tag));
             } else {
-                url_path.set_extension("html");
+                output.push_str(tag);
             }
+            rest = &rest[tag_end..];
+
+            if let Some(name) = tag_name(tag) {
+                let is_closing = tag.starts_with("</");
+                if PRESERVE_TAGS.contains(&name.as_str()) {
+                    if is_closing {
+                        if preserve_stack.last().is_some_and(|top| *top == name) {
+                            preserve_stack.pop();
+                        }
+                    } else if !tag.ends_with("/>") {
+                        preserve_stack.push(name);
+                    }
+                }
+            }
+            continue;
+        }
 
-            let loc_url = {
-                let path_str = url_path.to_string_lossy();
-                if path_str.is_empty() {
-                    "/".to_string()
+        let next_lt = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..next_lt];
+        rest = &rest[next_lt..];
+
+        if !preserve_stack.is_empty() {
+            output.push_str(text);
+        } else {
+            for ch in text.chars() {
+                if ch.is_whitespace() {
+                    pending_space = true;
                 } else {
-                    format!("/{}", path_str)
+                    if pending_space {
+                        output.push(' ');
+                        pending_space = false;
+                    }
+                    output.push(ch);
                 }
-            };
+            }
+        }
+    }
+
+    output
+}
+
+/// Drops the quotes around an attribute value when HTML5 allows it unquoted:
+/// the value must be non-empty and contain none of whitespace, `"`, `'`,
+/// `` ` ``, `=`, `<` or `>` — any of those would make the boundary between
+/// this attribute and the next ambiguous once the quotes are gone.
+fn unquote_attributes(tag: &str) -> String {
+    let mut out = String::with_capacity(tag.len());
+    let mut chars = tag.char_indices().peekable();
+
+    while let Some((_, ch)) = chars.next() {
+        if ch == '=' {
+            if let Some(&(quote_idx, quote)) = chars.peek() {
+                if quote == '"' || quote == '\'' {
+                    let value_start = quote_idx + quote.len_utf8();
+                    if let Some(rel_end) = tag[value_start..].find(quote) {
+                        let value = &tag[value_start..value_start + rel_end];
+                        let needs_quotes = value.is_empty()
+                            || value
+                                .chars()
+                                .any(|c| c.is_whitespace() || "\"'`=<>".contains(c));
+
+                        out.push('=');
+                        if needs_quotes {
+                            out.push(quote);
+                            out.push_str(value);
+                            out.push(quote);
+                        } else {
+                            out.push_str(value);
+                        }
+
+                        let value_end = value_start + rel_end + quote.len_utf8();
+                        while chars
+                            .peek()
+                            .is_some_and(|&(i, _)| i < value_end)
+                        {
+                            chars.next();
+                        }
+                        continue;
+                    }
+                }
+            }
+        }
 
+        out.push(ch);
+    }
+
+    out
+}
+
+fn tag_name(tag: &str) -> Option<String> {
+    let inner = tag
+        .trim_start_matches('<')
+        .trim_start_matches('/')
+        .trim_end_matches('>')
+        .trim_end_matches('/');
+    let name: String = inner
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '-')
+        .collect();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_lowercase())
+    }
+}
+
+pub fn generate_sitemap_xml(args: &Args, metadata_map: &MetadataMap) -> io::Result<()> {
+    let sitemap_path = args.target.join("sitemap.xml");
+
+    let default_changefreq = "monthly";
+    let base_priority = 0.5;
+
+    let mut entries = Vec::new();
+
+    for (rel_path, metadata) in metadata_map.iter() {
+        if metadata.include_in_sitemap.unwrap_or(true) {
+            let loc_url = generate_canonical_url(rel_path, &args.base_url);
             let source_path = args.source.join(rel_path);
             let last_mod = get_last_modified_date(&source_path);
 
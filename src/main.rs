@@ -6,40 +6,66 @@ mod io;
 mod site_map;
 mod nav;
 mod processing;
+mod markdown;
+mod html;
+mod search;
+mod highlighting;
+mod taxonomy;
+mod watch;
+mod math;
+mod mermaid;
+mod epub;
+mod gemtext;
+mod serve;
+mod backlinks;
+mod preview;
 
-use config::Args;
+use config::{Args, OutputFormat};
 use args::parse_args;
 use io::{read_template, print_info, print_error};
 use site_map::build_site_map;
 use processing::{process_directory, load_all_metadata_from_files}; // load_all_metadata_from_files is now correctly imported
 use nav::generate_all_index_files;
+use search::SearchIndexBuilder;
+use taxonomy::{build_taxonomy_index, generate_taxonomy_pages};
+use backlinks::{build_backlinks_index, BacklinksIndex};
 
 fn main() -> std::io::Result<()> {
     let args: Args = parse_args();
 
+    if args.enable_syntax_highlighting {
+        highlighting::validate_highlight_theme(&args.highlight_theme);
+    }
+
     if args.verbose {
         print_info(&format!("Verbose mode enabled."));
         print_info(&format!("Source directory: {}", args.source.display()));
         print_info(&format!("Target directory: {}", args.target.display()));
     }
 
-    if args.target.exists() && args.target.is_dir() {
-        if args.verbose {
-            print_info(&format!("Ensuring target directory structure exists: {}", args.target.display()));
+    if args.serve_port.is_none() {
+        if args.target.exists() && args.target.is_dir() {
+            if args.verbose {
+                print_info(&format!("Ensuring target directory structure exists: {}", args.target.display()));
+            }
+        } else {
+            if args.verbose {
+                print_info(&format!("Creating target directory: {}", args.target.display()));
+            }
         }
-    } else {
-        if args.verbose {
-            print_info(&format!("Creating target directory: {}", args.target.display()));
+
+        fs::create_dir_all(&args.target)?;
+
+        if args.enable_syntax_highlighting && args.highlight_theme == highlighting::CSS_CLASSES_THEME {
+            highlighting::write_css_theme_stylesheet(&args)?;
         }
     }
 
-    fs::create_dir_all(&args.target)?;
-
     let html_template = match read_template(&args.source, &args) {
         Ok(template) => template,
         Err(e) => {
             print_error(&format!("Template Error: {}", e));
-            return Err(e); 
+            return Err(e);
         }
     };
 
@@ -47,15 +73,78 @@ fn main() -> std::io::Result<()> {
     if args.verbose {
         print_info(&format!("Identified {} files for processing.", site_map.len()));
     }
-    
+
     // NEW: Load all metadata before processing any files
     let metadata_map = load_all_metadata_from_files(&args, &site_map)?;
 
+    if let Some(port) = args.serve_port {
+        return serve::run_serve(args, site_map, metadata_map, html_template, port);
+    }
+
+    match args.output_format {
+        OutputFormat::Epub => {
+            epub::generate_epub(&args, &site_map, &metadata_map, &html_template)?;
+            println!("Done generating EPUB.");
+            return Ok(());
+        }
+        OutputFormat::Gemini => {
+            gemtext::generate_gemini_site(&args, &site_map, &metadata_map)?;
+            println!("Done generating Gemini capsule.");
+            return Ok(());
+        }
+        OutputFormat::Gopher => {
+            gemtext::generate_gopher_site(&args, &site_map, &metadata_map)?;
+            println!("Done generating Gopher menu.");
+            return Ok(());
+        }
+        OutputFormat::Html => {}
+    }
+
+    let mut search_index = if args.enable_search_index {
+        Some(SearchIndexBuilder::new())
+    } else {
+        None
+    };
+
+    let backlinks_index = if args.enable_backlinks {
+        build_backlinks_index(&args, &site_map)
+    } else {
+        BacklinksIndex::default()
+    };
+
     // FIX: Pass the metadata_map to the processing and index generation functions
-    process_directory(&args, &site_map, &metadata_map, &args.source, &html_template)?;
-    
+    process_directory(
+        &args,
+        &site_map,
+        &metadata_map,
+        &backlinks_index,
+        &args.source,
+        &html_template,
+        &mut search_index,
+    )?;
+
     generate_all_index_files(&args, &site_map, &metadata_map, &html_template)?;
 
+    html::generate_sitemap_xml(&args, &metadata_map)?;
+
+    markdown::print_link_check_summary(&args);
+
+    let taxonomy_index = build_taxonomy_index(&metadata_map);
+    generate_taxonomy_pages(&args, &metadata_map, &taxonomy_index, &html_template)?;
+
+    if let Some(builder) = search_index {
+        builder.write(&args)?;
+    }
+
     println!("Done processing directories.");
+
+    if let Some(port) = args.preview_port {
+        return preview::run_preview(args, html_template, port);
+    }
+
+    if args.watch {
+        watch::watch_and_rebuild(&args, &html_template)?;
+    }
+
     Ok(())
 }
\ No newline at end of file
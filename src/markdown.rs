@@ -1,12 +1,20 @@
-use pulldown_cmark::{Event, HeadingLevel, LinkType, Parser, Tag};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, LinkType, Parser, Tag};
 use regex::Regex;
 use std::{
-    mem,
+    collections::{HashMap, HashSet},
+    fs, mem,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Condvar, Mutex, OnceLock,
+    },
 };
 
 use crate::config::{Args, MetadataMap, SiteMap};
+use crate::highlighting::highlight_code_block;
 use crate::io::{print_info, print_warning};
+use crate::math::render_math_to_html;
+use crate::mermaid::render_mermaid_to_svg;
 
 pub fn normalize_markdown_content(content: &str, _path: &Path) -> (String, bool) {
     let control_char_regex = Regex::new(r"[\p{Cc}\p{Cf}&&[^\n\t\r]]").unwrap();
@@ -103,28 +111,154 @@ pub fn prepare_content_for_parser(content: &str, metadata: &crate::config::PageM
     prepared
 }
 
-pub fn check_broken_links(content: &str, source_path: &Path, rel_path: &Path) {
-    let link_regex = Regex::new(r"\[[^\]]+\]\(([^):]+\.md)\)").unwrap();
+/// Caps how many external link checks may be in flight at once, so the
+/// parallel build (`process_directory` renders pages concurrently) can't open an
+/// unbounded number of outbound HTTP connections all at the same time.
+const MAX_CONCURRENT_EXTERNAL_CHECKS: usize = 8;
+
+/// Minimal counting semaphore: `acquire` blocks while no permits remain,
+/// `release` returns one and wakes a waiter. `ureq`'s blocking calls give us
+/// no async runtime to hang a more conventional bounded-pool executor off of.
+struct Semaphore {
+    permits: Mutex<usize>,
+    available: Condvar,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            permits: Mutex::new(permits),
+            available: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> SemaphoreGuard<'_> {
+        let mut permits = self.permits.lock().unwrap();
+        while *permits == 0 {
+            permits = self.available.wait(permits).unwrap();
+        }
+        *permits -= 1;
+        SemaphoreGuard { semaphore: self }
+    }
+}
+
+struct SemaphoreGuard<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphoreGuard<'_> {
+    fn drop(&mut self) {
+        *self.semaphore.permits.lock().unwrap() += 1;
+        self.semaphore.available.notify_one();
+    }
+}
+
+static EXTERNAL_LINK_SEMAPHORE: OnceLock<Semaphore> = OnceLock::new();
+static EXTERNAL_LINK_CACHE: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
+static BROKEN_LOCAL_LINKS: AtomicUsize = AtomicUsize::new(0);
+static BROKEN_EXTERNAL_LINKS: AtomicUsize = AtomicUsize::new(0);
+static BROKEN_ANCHORS: AtomicUsize = AtomicUsize::new(0);
+static EXTERNAL_LINKS_CHECKED: AtomicUsize = AtomicUsize::new(0);
+
+fn external_link_cache() -> &'static Mutex<HashMap<String, bool>> {
+    EXTERNAL_LINK_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn external_link_semaphore() -> &'static Semaphore {
+    EXTERNAL_LINK_SEMAPHORE.get_or_init(|| Semaphore::new(MAX_CONCURRENT_EXTERNAL_CHECKS))
+}
+
+/// Checks local `.md`/image/directory targets against the real output paths
+/// the build will actually produce — `site_map` for files and `all_dirs`
+/// (every directory under `args.source`, via
+/// [`crate::io::collect_all_dirs_robust`], since `generate_all_index_files`
+/// synthesizes an index page for every one of those, not just the ones with
+/// an `index.md`) — plus (new) `#fragment` anchors against the heading slugs
+/// of whichever document they target, and (when `args.check_external_links`
+/// is set) `http(s)` targets via a blocking HEAD request. Anchor and external
+/// results are aggregated into process-wide counters rather than printed
+/// per-link; call [`print_link_check_summary`] once the whole site has been
+/// processed to report them.
+pub fn check_broken_links(
+    content: &str,
+    rel_path: &Path,
+    site_map: &SiteMap,
+    all_dirs: &HashSet<PathBuf>,
+    args: &Args,
+) {
+    let link_regex = Regex::new(r"\[[^\]]+\]\(([^)]+)\)").unwrap();
     let image_link_regex = Regex::new(r"!\[[^\]]*\]\(([^)]+\.(png|jpe?g|gif|svg))\)").unwrap();
 
-    let parent_dir = source_path.parent().unwrap_or_else(|| Path::new(""));
+    let own_slugs = collect_heading_slugs(content);
 
     for caps in link_regex.captures_iter(content) {
-        let link_target = &caps[1];
-        let target_path = parent_dir.join(link_target);
-        if !target_path.exists() {
+        let target = caps[1].trim();
+
+        if let Some(fragment) = target.strip_prefix('#') {
+            if !own_slugs.contains(fragment) {
+                BROKEN_ANCHORS.fetch_add(1, Ordering::Relaxed);
+            }
+            continue;
+        }
+
+        if target.starts_with("http://") || target.starts_with("https://") {
+            if args.check_external_links {
+                check_external_link(target, args);
+            }
+            continue;
+        }
+
+        let (file_part, fragment) = match target.split_once('#') {
+            Some((file_part, fragment)) => (file_part, Some(fragment)),
+            None => (target, None),
+        };
+
+        if file_part.is_empty() {
+            continue;
+        }
+
+        let root_rel = resolve_link_path(rel_path, Path::new(file_part));
+        let target_rel = root_rel.strip_prefix("/").unwrap_or(&root_rel).to_path_buf();
+
+        let is_dir_style = file_part.ends_with('/') || Path::new(file_part).extension().is_none();
+
+        let exists = if is_dir_style {
+            all_dirs.contains(&target_rel) || site_map.contains(&target_rel.join("index.md"))
+        } else if file_part.ends_with(".md") {
+            site_map.contains(&target_rel)
+        } else {
+            // Not an in-tree link style we check (e.g. an external scheme
+            // our regex missed, or an already-rewritten `.html` href).
+            continue;
+        };
+
+        if !exists {
+            BROKEN_LOCAL_LINKS.fetch_add(1, Ordering::Relaxed);
             print_warning(&format!(
-                "Broken link detected in {}: Link to non-existent file '{}'",
+                "Broken link detected in {}: Link to non-existent page '{}'",
                 rel_path.display(),
-                link_target
+                file_part
             ));
+            continue;
+        }
+
+        if let Some(fragment) = fragment {
+            let target_source = args.source.join(&target_rel);
+            if let Ok(target_content) = fs::read_to_string(&target_source) {
+                if !collect_heading_slugs(&target_content).contains(fragment) {
+                    BROKEN_ANCHORS.fetch_add(1, Ordering::Relaxed);
+                }
+            }
         }
     }
 
     for caps in image_link_regex.captures_iter(content) {
         let link_target = &caps[1];
-        let target_path = parent_dir.join(link_target);
-        if !target_path.exists() {
+        let root_rel = resolve_link_path(rel_path, Path::new(link_target));
+        let target_rel = root_rel.strip_prefix("/").unwrap_or(&root_rel).to_path_buf();
+
+        if !site_map.contains(&target_rel) {
+            BROKEN_LOCAL_LINKS.fetch_add(1, Ordering::Relaxed);
             print_warning(&format!(
                 "Broken image link detected in {}: Link to non-existent image '{}'",
                 rel_path.display(),
@@ -134,13 +268,108 @@ pub fn check_broken_links(content: &str, source_path: &Path, rel_path: &Path) {
     }
 }
 
+/// Collects the heading-slug set a page would produce, independent of full
+/// event processing — used to validate `#fragment` targets without re-running
+/// the whole `process_markdown_events` pipeline on the linked-to document.
+fn collect_heading_slugs(content: &str) -> HashSet<String> {
+    let parser = Parser::new(content);
+    let mut slugs = HashSet::new();
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    let mut in_heading = false;
+    let mut buf = String::new();
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Heading(..)) => {
+                in_heading = true;
+                buf.clear();
+            }
+            Event::Text(text) if in_heading => buf.push_str(&text),
+            Event::End(Tag::Heading(..)) => {
+                in_heading = false;
+                let base = slugify_heading(buf.trim());
+                slugs.insert(dedupe_slug(&mut counts, &base));
+            }
+            _ => {}
+        }
+    }
+
+    slugs
+}
+
+/// Issues a blocking HEAD request for `url`, deduping by exact URL through a
+/// process-wide cache so a URL already checked is not requested again (two
+/// pages checking the same uncached URL at the same moment can both request
+/// it), and skipping any domain named in `args.link_check_skip_domains`.
+fn check_external_link(url: &str, args: &Args) {
+    if args
+        .link_check_skip_domains
+        .iter()
+        .any(|domain| url.contains(domain.as_str()))
+    {
+        return;
+    }
+
+    if let Some(&ok) = external_link_cache().lock().unwrap().get(url) {
+        if !ok {
+            BROKEN_EXTERNAL_LINKS.fetch_add(1, Ordering::Relaxed);
+        }
+        return;
+    }
+
+    EXTERNAL_LINKS_CHECKED.fetch_add(1, Ordering::Relaxed);
+
+    let _permit = external_link_semaphore().acquire();
+    let ok = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(args.link_check_timeout_secs))
+        .build()
+        .head(url)
+        .call()
+        .is_ok();
+
+    external_link_cache().lock().unwrap().insert(url.to_string(), ok);
+
+    if !ok {
+        BROKEN_EXTERNAL_LINKS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Reports the aggregate link-check results for the whole build in a single
+/// message instead of one warning per broken link, then resets the counters
+/// so the next build (a `--watch`/`preview` rebuild) starts from zero.
+pub fn print_link_check_summary(args: &Args) {
+    let broken_local = BROKEN_LOCAL_LINKS.swap(0, Ordering::Relaxed);
+    let broken_anchors = BROKEN_ANCHORS.swap(0, Ordering::Relaxed);
+    let broken_external = BROKEN_EXTERNAL_LINKS.swap(0, Ordering::Relaxed);
+    let checked_external = EXTERNAL_LINKS_CHECKED.swap(0, Ordering::Relaxed);
+
+    if broken_local == 0 && broken_anchors == 0 && broken_external == 0 {
+        print_info("Link check: no broken local links, anchors, or external URLs found.");
+        return;
+    }
+
+    let external_summary = if args.check_external_links {
+        format!(
+            ", {} broken external link(s) ({} checked)",
+            broken_external, checked_external
+        )
+    } else {
+        String::new()
+    };
+
+    print_warning(&format!(
+        "Link check summary: {} broken local link(s), {} broken anchor(s){}",
+        broken_local, broken_anchors, external_summary
+    ));
+}
+
 pub fn process_markdown_events<'a>(
     args: &Args,
     site_map: &SiteMap,
     metadata_map: &MetadataMap,
     parser: Parser<'a, 'a>,
     path_rel: &Path,
-) -> (String, String) {
+) -> (String, String, String, bool, bool) {
     let mut title_h1 = String::new();
     let mut in_h1 = false;
     let mut events = Vec::new();
@@ -151,13 +380,103 @@ pub fn process_markdown_events<'a>(
     let mut current_heading_id: Option<String> = None;
     let mut current_heading_classes: Option<Vec<String>> = None;
 
+    let mut in_heading = false;
+    let mut heading_text_buf = String::new();
+    let mut heading_explicit_id: Option<String> = None;
+    let mut heading_level_for_toc = HeadingLevel::H1;
+    let mut heading_start_idx: Option<usize> = None;
+    let mut slug_counts: HashMap<String, u32> = HashMap::new();
+    let mut toc_entries: Vec<(HeadingLevel, String, String)> = Vec::new();
+
     let mut in_link = false;
     let mut current_link_dest: Option<String> = None;
     let mut link_text_events: Vec<Event> = Vec::new();
     let mut should_auto_title = false;
 
+    let mut in_code_block = false;
+    let mut code_block_lang = String::new();
+    let mut code_block_buf = String::new();
+
+    let mut uses_math = false;
+    let mut uses_mermaid = false;
+
+    let page_metadata = metadata_map.get(path_rel);
+    let highlighting_disabled_for_page = page_metadata
+        .and_then(|m| m.disable_highlighting)
+        .unwrap_or(false);
+    let page_highlight_theme = page_metadata
+        .and_then(|m| m.highlight_theme.as_deref())
+        .unwrap_or(&args.highlight_theme);
+    let math_prerendered = page_metadata
+        .and_then(|m| m.math_mode.as_deref())
+        .is_some_and(|mode| mode == "prerendered");
+    let mermaid_prerendered = page_metadata
+        .and_then(|m| m.mermaid_mode.as_deref())
+        .is_some_and(|mode| mode == "prerendered");
+    let math_disabled_for_page = page_metadata.and_then(|m| m.disable_math).unwrap_or(false);
+    let mermaid_disabled_for_page = page_metadata
+        .and_then(|m| m.disable_mermaid)
+        .unwrap_or(false);
+
     for event in parser {
         match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                in_code_block = true;
+                code_block_buf.clear();
+                code_block_lang = match &kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+
+                let rendered = if args.enable_mermaid
+                    && !mermaid_disabled_for_page
+                    && code_block_lang == "mermaid"
+                {
+                    let prerendered_svg = mermaid_prerendered
+                        .then_some(args.mermaid_renderer_path.as_deref())
+                        .flatten()
+                        .and_then(|mmdc_path| {
+                            render_mermaid_to_svg(&code_block_buf, mmdc_path)
+                        });
+
+                    match prerendered_svg {
+                        Some(svg) => svg,
+                        None => {
+                            uses_mermaid = true;
+                            format!(
+                                "<div class=\"mermaid\">{}</div>",
+                                escape_toc_text(&code_block_buf)
+                            )
+                        }
+                    }
+                } else if args.enable_math_rendering
+                    && !math_disabled_for_page
+                    && code_block_lang == "math"
+                {
+                    if math_prerendered {
+                        render_math_to_html(&code_block_buf, true)
+                    } else {
+                        uses_math = true;
+                        format!(
+                            "<div class=\"math display\">{}</div>",
+                            escape_toc_text(&code_block_buf)
+                        )
+                    }
+                } else if args.enable_syntax_highlighting && !highlighting_disabled_for_page {
+                    highlight_code_block(&code_block_buf, &code_block_lang, page_highlight_theme)
+                } else {
+                    let escaped = code_block_buf
+                        .replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;");
+                    format!("<pre><code>{}</code></pre>", escaped)
+                };
+
+                events.push(Event::Html(rendered.into()));
+            }
             Event::Start(Tag::Heading(level, id, classes_from_event)) => {
                 _current_heading_level = Some(level);
                 current_heading_id = id.map(|s| s.to_string());
@@ -173,6 +492,12 @@ pub fn process_markdown_events<'a>(
                     current_heading_classes = None;
                 }
 
+                in_heading = true;
+                heading_text_buf.clear();
+                heading_explicit_id = id.clone().map(|s| s.to_string());
+                heading_level_for_toc = level;
+                heading_start_idx = Some(events.len());
+
                 if !first_heading_found {
                     first_heading_found = true;
                     in_h1 = true;
@@ -186,6 +511,27 @@ pub fn process_markdown_events<'a>(
                 }
             }
             Event::End(Tag::Heading(level, id, classes)) => {
+                in_heading = false;
+                let heading_text = heading_text_buf.trim().to_string();
+
+                let base_slug = heading_explicit_id
+                    .take()
+                    .unwrap_or_else(|| slugify_heading(&heading_text));
+                let final_slug = dedupe_slug(&mut slug_counts, &base_slug);
+
+                if let Some(idx) = heading_start_idx.take() {
+                    if let Event::Start(Tag::Heading(lvl, _, patched_classes)) = events[idx].clone()
+                    {
+                        events[idx] = Event::Start(Tag::Heading(
+                            lvl,
+                            Some(final_slug.clone().into()),
+                            patched_classes,
+                        ));
+                    }
+                }
+
+                toc_entries.push((heading_level_for_toc, final_slug, heading_text));
+
                 if in_h1 {
                     in_h1 = false;
                     mem::take(&mut current_heading_id);
@@ -199,10 +545,19 @@ pub fn process_markdown_events<'a>(
                 mem::take(&mut _current_heading_level);
             }
             Event::Text(text) => {
+                if in_code_block {
+                    code_block_buf.push_str(&text);
+                    continue;
+                }
+
                 if in_h1 {
                     title_h1.push_str(&text);
                 }
 
+                if in_heading {
+                    heading_text_buf.push_str(&text);
+                }
+
                 if in_link {
                     let trimmed = text.trim();
                     if trimmed == "{title}" || trimmed == "{TITLE}" {
@@ -212,7 +567,15 @@ pub fn process_markdown_events<'a>(
                     continue;
                 }
 
-                events.push(Event::Text(text));
+                if args.enable_math_rendering && !math_disabled_for_page && !in_heading {
+                    let (math_events, found_math) = render_math_spans(&text, math_prerendered);
+                    if found_math && !math_prerendered {
+                        uses_math = true;
+                    }
+                    events.extend(math_events);
+                } else {
+                    events.push(Event::Text(text));
+                }
             }
             Event::Start(Tag::Link(link_type, dest, title_attr)) => {
                 in_link = true;
@@ -329,9 +692,247 @@ pub fn process_markdown_events<'a>(
     } else {
         path_rel.to_string_lossy().to_string()
     };
+    let toc_html = build_toc_html(&toc_entries);
+    let toc_disabled_for_page = page_metadata.and_then(|m| m.disable_toc).unwrap_or(false);
+
+    // mdBook-style in-place marker: a line containing only `[[TOC]]` is
+    // replaced with the same nested list returned to the caller, so authors
+    // can either place the TOC via the template slot or inline it themselves.
+    // pulldown-cmark wraps that bare line in its own `Start`/`End(Paragraph)`,
+    // so when the marker is the paragraph's sole content those are dropped
+    // too — otherwise the substitution becomes a `<ul>` nested inside a
+    // `<p>`, invalid HTML that browsers auto-close inconsistently. A page
+    // with `disable_toc: true` gets the marker spliced out to nothing, the
+    // same opt-out the separate `{{ toc_html }}` template placeholder honors.
+    let inline_toc_replacement = if toc_disabled_for_page {
+        String::new()
+    } else {
+        toc_html.clone()
+    };
+    // pulldown-cmark emits unmatched `[`/`]` as separate `Text` events, so
+    // adjacent text runs are merged first for the marker to arrive as one.
+    let mut merged_events: Vec<Event> = Vec::with_capacity(events.len());
+    for event in events {
+        if let (Some(Event::Text(prev)), Event::Text(next)) = (merged_events.last_mut(), &event) {
+            *prev = format!("{}{}", &**prev, &**next).into();
+            continue;
+        }
+        merged_events.push(event);
+    }
+    let events = merged_events;
+    let mut spliced_events = Vec::with_capacity(events.len());
+    let mut i = 0;
+    while i < events.len() {
+        let is_marker = matches!(&events[i], Event::Text(text) if text.trim() == "[[TOC]]");
+
+        if is_marker {
+            let in_own_paragraph = i > 0
+                && matches!(events[i - 1], Event::Start(Tag::Paragraph))
+                && matches!(events.get(i + 1), Some(Event::End(Tag::Paragraph)));
+
+            if in_own_paragraph {
+                spliced_events.pop();
+                spliced_events.push(Event::Html(inline_toc_replacement.clone().into()));
+                i += 2;
+            } else {
+                spliced_events.push(Event::Html(inline_toc_replacement.clone().into()));
+                i += 1;
+            }
+        } else {
+            spliced_events.push(events[i].clone());
+            i += 1;
+        }
+    }
+    let events = spliced_events;
+
     let html_from_events = events_to_html(events);
     let final_content = html_output + &html_from_events;
-    (final_content, final_title)
+    (final_content, final_title, toc_html, uses_math, uses_mermaid)
+}
+
+/// Scans a text run for `$$...$$` (display) and `$...$` (inline) math spans,
+/// splitting it into a sequence of plain-text and wrapper-element events.
+/// A backslash-escaped `\$` never opens or closes a span, and a delimiter
+/// must close within the same run (no span crosses a newline). When
+/// `prerendered` is set, each span is rendered to static HTML via
+/// [`render_math_to_html`] instead of being wrapped for the client-side
+/// KaTeX script.
+fn render_math_spans(text: &str, prerendered: bool) -> (Vec<Event<'static>>, bool) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut events = Vec::new();
+    let mut plain = String::new();
+    let mut found_math = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '\\' && i + 1 < chars.len() && chars[i + 1] == '$' {
+            plain.push('$');
+            i += 2;
+            continue;
+        }
+
+        if chars[i] == '$' {
+            let display = i + 1 < chars.len() && chars[i + 1] == '$';
+            let delim_len = if display { 2 } else { 1 };
+            let search_from = i + delim_len;
+
+            if let Some(close) = find_math_close(&chars, search_from, display) {
+                if !plain.is_empty() {
+                    events.push(Event::Text(mem::take(&mut plain).into()));
+                }
+
+                let tex: String = chars[search_from..close].iter().collect();
+                let rendered = if prerendered {
+                    render_math_to_html(&tex, display)
+                } else {
+                    let class = if display { "math display" } else { "math inline" };
+                    format!("<span class=\"{}\">{}</span>", class, escape_toc_text(&tex))
+                };
+                events.push(Event::Html(rendered.into()));
+                found_math = true;
+
+                i = close + delim_len;
+                continue;
+            }
+        }
+
+        plain.push(chars[i]);
+        i += 1;
+    }
+
+    if !plain.is_empty() {
+        events.push(Event::Text(plain.into()));
+    }
+
+    (events, found_math)
+}
+
+/// Finds the index of the closing `$`/`$$` starting at `from`, honoring
+/// `\$` escapes and refusing to match across a newline.
+fn find_math_close(chars: &[char], from: usize, display: bool) -> Option<usize> {
+    let mut i = from;
+    while i < chars.len() {
+        if chars[i] == '\\' && i + 1 < chars.len() && chars[i + 1] == '$' {
+            i += 2;
+            continue;
+        }
+
+        if chars[i] == '\n' {
+            return None;
+        }
+
+        if chars[i] == '$' {
+            if !display {
+                return Some(i);
+            }
+            if i + 1 < chars.len() && chars[i + 1] == '$' {
+                return Some(i);
+            }
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+/// GitHub-style heading slug: lowercase, non-alphanumeric runs collapsed to a
+/// single hyphen, leading/trailing hyphens trimmed.
+fn slugify_heading(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut last_was_hyphen = true; // swallow a leading separator
+
+    for ch in text.to_lowercase().chars() {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+            last_was_hyphen = false;
+        } else if !last_was_hyphen {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
+
+/// De-duplicates a slug against previously seen slugs on the same page by
+/// appending a numeric suffix (`-1`, `-2`, ...) on collision.
+fn dedupe_slug(seen: &mut HashMap<String, u32>, base: &str) -> String {
+    let count = seen.entry(base.to_string()).or_insert(0);
+    let slug = if *count == 0 {
+        base.to_string()
+    } else {
+        format!("{}-{}", base, count)
+    };
+    *count += 1;
+    slug
+}
+
+/// Builds a nested `<ul>` tree from the page's headings in document order,
+/// pushing/popping a level stack so deeper headings nest under shallower ones.
+fn build_toc_html(entries: &[(HeadingLevel, String, String)]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::new();
+    let mut stack: Vec<HeadingLevel> = Vec::new();
+
+    for (level, slug, text) in entries {
+        while stack.last().is_some_and(|top| *top > *level) {
+            html.push_str("</li></ul>");
+            stack.pop();
+        }
+
+        if stack.last() == Some(level) {
+            html.push_str("</li>");
+        } else {
+            html.push_str("<ul>");
+            stack.push(*level);
+        }
+
+        html.push_str(&format!(
+            "<li><a href=\"#{}\">{}</a>",
+            slug,
+            escape_toc_text(text)
+        ));
+    }
+
+    while stack.pop().is_some() {
+        html.push_str("</li></ul>");
+    }
+
+    html
+}
+
+fn escape_toc_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Flattens a page to the words a search index should see: every text run
+/// (prose, fenced/indented code-block contents, image alt text) plus inline
+/// code spans (so e.g. a function name mentioned only in backticks is still
+/// findable), dropping everything else (link targets, raw HTML).
+pub fn extract_plain_text(content: &str) -> String {
+    let parser = Parser::new(content);
+    let mut text = String::new();
+
+    for event in parser {
+        let run = match event {
+            Event::Text(run) => run,
+            Event::Code(run) => run,
+            _ => continue,
+        };
+
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&run);
+    }
+
+    text
 }
 
 fn events_to_html(events: Vec<Event>) -> String {
@@ -340,7 +941,7 @@ fn events_to_html(events: Vec<Event>) -> String {
     html_output
 }
 
-fn resolve_link_path(from_path_rel: &Path, link_target: &Path) -> PathBuf {
+pub(crate) fn resolve_link_path(from_path_rel: &Path, link_target: &Path) -> PathBuf {
     if link_target.to_string_lossy().starts_with('/') {
         return link_target.to_path_buf();
     }
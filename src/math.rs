@@ -0,0 +1,29 @@
+use crate::io::print_warning;
+
+/// Server-side KaTeX rendering for pages opting into `math_mode = "prerendered"`,
+/// so the generated page ships fully-formed MathML/HTML and never depends on
+/// the client-side KaTeX script pulled in by [`crate::html::KATEX_HEAD_INCLUDE`].
+pub fn render_math_to_html(tex: &str, display: bool) -> String {
+    let opts = katex::Opts::builder()
+        .display_mode(display)
+        .build()
+        .unwrap_or_default();
+
+    match katex::render_with_opts(tex, &opts) {
+        Ok(html) => html,
+        Err(e) => {
+            print_warning(&format!(
+                "KaTeX server-side render failed for '{}': {}",
+                tex, e
+            ));
+            let class = if display { "math display" } else { "math inline" };
+            format!("<span class=\"{}\">{}</span>", class, escape(tex))
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,47 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::io::print_warning;
+
+/// Shells out to a configured `mmdc` (mermaid-cli) binary to render a
+/// diagram definition to inline SVG, so the page ships script-free. Returns
+/// `None` on any spawn/IO/exit failure; callers fall back to the plain
+/// client-rendered `<div class="mermaid">` container in that case.
+pub fn render_mermaid_to_svg(diagram: &str, mmdc_path: &str) -> Option<String> {
+    let mut child = Command::new(mmdc_path)
+        .args(["-i", "-", "-o", "-", "-e", "svg"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| print_warning(&format!("Failed to spawn '{}': {}", mmdc_path, e)))
+        .ok()?;
+
+    // Writing the full diagram to stdin before draining stdout/stderr would
+    // deadlock once the diagram is large enough to fill the OS pipe buffer:
+    // mmdc blocks writing stdout while we're still blocked writing stdin. Do
+    // the stdin write on its own thread so it proceeds concurrently with
+    // `wait_with_output()`'s stdout/stderr draining below.
+    let mut stdin = child.stdin.take()?;
+    let diagram = diagram.to_string();
+    let writer = thread::spawn(move || stdin.write_all(diagram.as_bytes()));
+
+    let output = child.wait_with_output().ok()?;
+
+    if let Err(e) = writer.join().unwrap_or(Ok(())) {
+        print_warning(&format!("Failed to write diagram to mmdc stdin: {}", e));
+        return None;
+    }
+
+    if !output.status.success() {
+        print_warning(&format!(
+            "mmdc exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok()
+}
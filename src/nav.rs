@@ -4,17 +4,107 @@ use std::{
     path::{Path, PathBuf},
     collections::BTreeMap
 };
-use regex::Regex; 
+use regex::Regex;
 use pulldown_cmark::Parser;
-use crate::config::{Args, NavItem, NavTree, SiteMap, MetadataMap, PageMetadata}; 
+use rayon::prelude::*;
+use crate::config::{Args, NavItem, NavTree, SiteMap, MetadataMap, PageMetadata};
 use crate::io::{collect_all_dirs_robust, print_error, print_info};
-use crate::processing::{rewrite_link_to_relative, process_markdown_events, format_html_page, get_last_modified_date}; 
+use crate::processing::{rewrite_link_to_relative, process_markdown_events, format_html_page, get_last_modified_date};
+use crate::taxonomy::slugify_term;
 
-pub fn generate_navigation_html(args: &Args, site_map: &SiteMap, metadata_map: &MetadataMap, current_rel_path: &Path) -> String { 
+pub fn generate_navigation_html(args: &Args, site_map: &SiteMap, metadata_map: &MetadataMap, current_rel_path: &Path) -> String {
     let nav_tree = build_nav_tree(site_map, metadata_map, current_rel_path);
     nav_tree_to_html(&nav_tree, current_rel_path, site_map, args, true)
 }
 
+/// Walks a `NavItem` tree in exactly the order `nav_tree_to_html` renders it
+/// (all subdirectories, depth-first, before any of a directory's own files),
+/// producing the linear page sequence prev/next links are derived from. Only
+/// files backed by a real `.md` source in `site_map` are included, so
+/// synthetic branches like the "Tags" taxonomy listing (no source file of
+/// their own) never get spliced between two unrelated articles.
+pub fn flatten_nav_order(nav_item: &NavItem, site_map: &SiteMap) -> Vec<PathBuf> {
+    let mut order = Vec::new();
+    flatten_nav_order_inner(nav_item, site_map, &mut order);
+    order
+}
+
+fn flatten_nav_order_inner(nav_item: &NavItem, site_map: &SiteMap, order: &mut Vec<PathBuf>) {
+    if let NavItem::Directory { children, .. } = nav_item {
+        for child in children.values() {
+            if matches!(child, NavItem::Directory { .. }) {
+                flatten_nav_order_inner(child, site_map, order);
+            }
+        }
+        for child in children.values() {
+            if let NavItem::File { rel_path, .. } = child {
+                if site_map.contains(rel_path) {
+                    order.push(rel_path.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Finds `current_rel_path`'s immediate predecessor/successor in nav order,
+/// each resolved to a `(display name, relative link)` pair. Returns `(None,
+/// None)` for a page absent from the nav tree (e.g. `exclude_from_nav`).
+fn find_prev_next(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    current_rel_path: &Path,
+) -> (Option<(String, String)>, Option<(String, String)>) {
+    let nav_tree = build_nav_tree(site_map, metadata_map, current_rel_path);
+    let order = flatten_nav_order(&nav_tree, site_map);
+
+    let Some(index) = order.iter().position(|p| p == current_rel_path) else {
+        return (None, None);
+    };
+
+    let resolve = |rel_path: &Path| -> (String, String) {
+        let name = metadata_map
+            .get(rel_path)
+            .and_then(|m| m.nav_title.clone().or_else(|| m.computed_title.clone()))
+            .unwrap_or_else(|| rel_path.to_string_lossy().to_string());
+        let site_root_path = PathBuf::from("/").join(rel_path);
+        let link = rewrite_link_to_relative(current_rel_path, &site_root_path, site_map, args.verbose);
+        (name, link)
+    };
+
+    let prev = index.checked_sub(1).map(|i| resolve(&order[i]));
+    let next = order.get(index + 1).map(|p| resolve(p));
+
+    (prev, next)
+}
+
+/// Renders the `<nav class="prev-next">` block for one page, or an empty
+/// string (never the raw `{{ prev_next_html }}` placeholder) when the page
+/// has neither a predecessor nor a successor.
+pub fn render_prev_next_html(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    current_rel_path: &Path,
+) -> String {
+    let (prev, next) = find_prev_next(args, site_map, metadata_map, current_rel_path);
+
+    if prev.is_none() && next.is_none() {
+        return String::new();
+    }
+
+    let mut html = String::from(r#"<nav class="prev-next">"#);
+    if let Some((name, link)) = &prev {
+        html.push_str(&format!(r#"<a class="prev-page" href="{}">&larr; {}</a>"#, link, name));
+    }
+    if let Some((name, link)) = &next {
+        html.push_str(&format!(r#"<a class="next-page" href="{}">{} &rarr;</a>"#, link, name));
+    }
+    html.push_str("</nav>");
+
+    html
+}
+
 // Helper 1/4: Determines which directories should be excluded based on their index.md metadata.
 fn get_excluded_directories(site_map: &SiteMap, metadata_map: &MetadataMap) -> std::collections::HashSet<PathBuf> {
     let default_metadata = PageMetadata::default();
@@ -217,15 +307,19 @@ fn build_nav_tree(site_map: &SiteMap, metadata_map: &MetadataMap, current_rel_pa
 
         // 4. Traverse and insert the item into the tree
         insert_item_into_tree(
-            &mut root_children, 
-            rel_path, 
-            metadata_map, 
-            &current_html_path, 
-            file_name, 
+            &mut root_children,
+            rel_path,
+            metadata_map,
+            &current_html_path,
+            file_name,
             insertion_key
         );
     }
 
+    if let Some(tags_branch) = build_tags_nav_branch(metadata_map, &current_html_path) {
+        root_children.insert("tags".to_string(), tags_branch);
+    }
+
     NavItem::Directory {
         rel_path: PathBuf::new(),
         name: "Root".to_string(),
@@ -233,6 +327,43 @@ fn build_nav_tree(site_map: &SiteMap, metadata_map: &MetadataMap, current_rel_pa
     }
 }
 
+/// Surfaces every term in the `"tags"` bucket of the merged `TaxonomyIndex`
+/// (both the `tags` shorthand and `taxonomies = { tags = [...] }` fold into
+/// this same bucket via `build_taxonomy_index`) as a synthetic "Tags" branch
+/// pointing at the `target/tags/<slug>/index.html` listing pages
+/// `generate_taxonomy_pages` writes — these have no `.md` source, so they're
+/// synthesized here rather than discovered via `site_map`. Returns `None`
+/// when no page declares any tags.
+fn build_tags_nav_branch(metadata_map: &MetadataMap, current_html_path: &Path) -> Option<NavItem> {
+    let taxonomy_index = crate::taxonomy::build_taxonomy_index(metadata_map);
+    let tags = taxonomy_index.get(crate::taxonomy::TAGS_TAXONOMY)?;
+
+    if tags.is_empty() {
+        return None;
+    }
+
+    let mut children: NavTree = BTreeMap::new();
+    for tag in tags.keys().cloned() {
+        let slug = slugify_term(&tag);
+        let rel_path = PathBuf::from("tags").join(&slug).join("index.md");
+        let is_current = rel_path.with_extension("html") == *current_html_path;
+        children.insert(
+            slug,
+            NavItem::File {
+                rel_path,
+                name: tag,
+                is_current,
+            },
+        );
+    }
+
+    Some(NavItem::Directory {
+        rel_path: PathBuf::from("tags"),
+        name: "Tags".to_string(),
+        children,
+    })
+}
+
 fn nav_tree_to_html(nav_item: &NavItem, current_rel_path: &Path, site_map: &SiteMap, args: &Args, is_root: bool) -> String {
     use NavItem::*;
     match nav_item {
@@ -323,119 +454,149 @@ fn nav_tree_to_html(nav_item: &NavItem, current_rel_path: &Path, site_map: &Site
     }
 }
 
-pub fn generate_all_index_files(args: &Args, site_map: &SiteMap, metadata_map: &MetadataMap, html_template: &str) -> io::Result<()> {
-    let dirs_to_index = collect_all_dirs_robust(&args.source)?;
-    let mut sorted_dirs: Vec<PathBuf> = dirs_to_index.into_iter().collect();
-    sorted_dirs.sort();
-    
+/// Renders the synthetic `index.html` for one directory (either from its
+/// `index.md`, if one exists, or a bare directory-listing placeholder).
+/// Pulled out of `generate_all_index_files` so each directory's independent
+/// work can be driven through `rayon::par_iter`.
+fn generate_index_file_for_dir(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    html_template: &str,
+    json_regex: &Regex,
+    rel_dir_path: &Path,
+) -> io::Result<()> {
     let default_index_metadata = PageMetadata::default();
-    
-    let json_regex = Regex::new(r"(?s)```json\s*(\{.*?\})\s*```\s*(\s*)$").unwrap();
 
-    for rel_dir_path in sorted_dirs {
-        let index_md_path = rel_dir_path.join("index.md");
-        let path_target_dir = args.target.join(&rel_dir_path);
-        let path_target = path_target_dir.join("index.html");
+    let index_md_path = rel_dir_path.join("index.md");
+    let path_target_dir = args.target.join(rel_dir_path);
+    let path_target = path_target_dir.join("index.html");
 
-        let has_index_md = site_map.contains(&index_md_path);
-        let index_metadata = metadata_map.get(&index_md_path).unwrap_or(&default_index_metadata);
+    let has_index_md = site_map.contains(&index_md_path);
+    let index_metadata = metadata_map.get(&index_md_path).unwrap_or(&default_index_metadata);
 
-        if has_index_md && index_metadata.avoid_generation.unwrap_or(false) {
-            if args.verbose {
-                print_info(&format!("Skipped (Avoid Generation): {}", index_md_path.display()));
-            }
-            continue;
+    if has_index_md && index_metadata.avoid_generation.unwrap_or(false) {
+        if args.verbose {
+            print_info(&format!("Skipped (Avoid Generation): {}", index_md_path.display()));
         }
+        return Ok(());
+    }
 
-        let (title, content) = if has_index_md {
-            let path_source = args.source.join(&index_md_path);
-            let markdown_input = fs::read_to_string(&path_source)?;
-            
-            let content_without_json = json_regex.replace_all(&markdown_input, |caps: &regex::Captures| {
-                caps.get(2).map_or("", |m| m.as_str()).to_string()
-            }).to_string();
+    let (title, content) = if has_index_md {
+        let path_source = args.source.join(&index_md_path);
+        let markdown_input = fs::read_to_string(&path_source)?;
 
-            let parser = Parser::new(&content_without_json);
-            let (html_output, title_from_h1) = process_markdown_events(args, site_map, parser, &index_md_path);
-            
-            let final_title = index_metadata.page_title.as_ref().unwrap_or(&title_from_h1).clone();
-            (final_title, html_output)
-        } else {
-            let default_title = if rel_dir_path.as_os_str().is_empty() {
-                "Root Index".to_string()
-            } else {
-                rel_dir_path.to_string_lossy().to_string()
-            };
-            ("Index: ".to_string() + &default_title, String::new())
-        };
+        let content_without_json = json_regex.replace_all(&markdown_input, |caps: &regex::Captures| {
+            caps.get(2).map_or("", |m| m.as_str()).to_string()
+        }).to_string();
 
-        let source_path_rel_str = if has_index_md {
-            index_md_path.to_string_lossy().into_owned()
-        } else {
-            rel_dir_path.to_string_lossy().into_owned()
-        };
-        
-        let source_path_display = if source_path_rel_str.is_empty() {
-            "/".to_string()
-        } else {
-            format!("/{}", source_path_rel_str)
-        };
+        let parser = Parser::new(&content_without_json);
+        let (html_output, title_from_h1) = process_markdown_events(args, site_map, parser, &index_md_path);
 
-        let source_path_real = if has_index_md {
-            args.source.join(&index_md_path)
-        } else {
-            args.source.join(&rel_dir_path)
-        };
-        
-        let nav_rel_path = if has_index_md {
-            index_md_path.clone()
-        } else {
-            rel_dir_path.join("index.md") 
-        };
-        
-        let nav_html = generate_navigation_html(args, site_map, metadata_map, &nav_rel_path);
-        
-        let last_modified = get_last_modified_date(&source_path_real);
-        let default_content = if content.is_empty() {
-            format!("<h1>{}</h1><p>Use the links on the left to access content.</p>", title)
+        let final_title = index_metadata.page_title.as_ref().unwrap_or(&title_from_h1).clone();
+        (final_title, html_output)
+    } else {
+        let default_title = if rel_dir_path.as_os_str().is_empty() {
+            "Root Index".to_string()
         } else {
-            content
+            rel_dir_path.to_string_lossy().to_string()
         };
+        ("Index: ".to_string() + &default_title, String::new())
+    };
 
-        let final_html = format_html_page(
-            &title, 
-            &source_path_display, 
-            &last_modified,
-            &nav_html, 
-            &default_content, 
-            html_template
-        );
+    let source_path_rel_str = if has_index_md {
+        index_md_path.to_string_lossy().into_owned()
+    } else {
+        rel_dir_path.to_string_lossy().into_owned()
+    };
 
-        fs::create_dir_all(&path_target_dir)?;
-        
-         if path_target.exists() {
-            if let Ok(existing_content) = fs::read_to_string(&path_target) {
-                if existing_content == final_html {
-                    if args.verbose {
-                        print_info(&format!("Skipped (Unchanged Index HTML): {}", path_target.display()));
-                    }
-                    continue;
-                }
-            }
-        }
+    let source_path_display = if source_path_rel_str.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{}", source_path_rel_str)
+    };
+
+    let source_path_real = if has_index_md {
+        args.source.join(&index_md_path)
+    } else {
+        args.source.join(rel_dir_path)
+    };
 
-        match fs::write(&path_target, final_html) {
-            Ok(_) => {
+    let nav_rel_path = if has_index_md {
+        index_md_path.clone()
+    } else {
+        rel_dir_path.join("index.md")
+    };
+
+    let nav_html = generate_navigation_html(args, site_map, metadata_map, &nav_rel_path);
+
+    let last_modified = get_last_modified_date(&source_path_real);
+    let default_content = if content.is_empty() {
+        format!("<h1>{}</h1><p>Use the links on the left to access content.</p>", title)
+    } else {
+        content
+    };
+
+    let final_html = format_html_page(
+        &title,
+        &source_path_display,
+        &last_modified,
+        &nav_html,
+        &default_content,
+        html_template
+    );
+
+    fs::create_dir_all(&path_target_dir)?;
+
+    if path_target.exists() {
+        if let Ok(existing_content) = fs::read_to_string(&path_target) {
+            if existing_content == final_html {
                 if args.verbose {
-                    print_info(&format!("Successfully generated index.html at: {}", path_target.display()));
+                    print_info(&format!("Skipped (Unchanged Index HTML): {}", path_target.display()));
                 }
+                return Ok(());
             }
-            Err(e) => {
-                print_error(&format!("Failed to write index.html to {}: {}", path_target.display(), e));
-                return Err(e);
+        }
+    }
+
+    match fs::write(&path_target, final_html) {
+        Ok(_) => {
+            if args.verbose {
+                print_info(&format!("Successfully generated index.html at: {}", path_target.display()));
             }
+            Ok(())
+        }
+        Err(e) => {
+            print_error(&format!("Failed to write index.html to {}: {}", path_target.display(), e));
+            Err(e)
         }
     }
+}
+
+/// Generates every directory's `index.html`. Each directory's output is
+/// independent (its own `nav_html`, markdown parse, unchanged-file skip
+/// check, and `fs::write`), so, as Zola does for page rendering, the work
+/// is driven through `rayon::par_iter` rather than a sequential loop. The
+/// first error in `sorted_dirs` order is returned, so `verbose` logging and
+/// the process exit code stay deterministic regardless of which directory's
+/// worker thread finishes first.
+pub fn generate_all_index_files(args: &Args, site_map: &SiteMap, metadata_map: &MetadataMap, html_template: &str) -> io::Result<()> {
+    let dirs_to_index = collect_all_dirs_robust(&args.source)?;
+    let mut sorted_dirs: Vec<PathBuf> = dirs_to_index.into_iter().collect();
+    sorted_dirs.sort();
+
+    let json_regex = Regex::new(r"(?s)```json\s*(\{.*?\})\s*```\s*(\s*)$").unwrap();
+
+    let results: Vec<io::Result<()>> = sorted_dirs
+        .par_iter()
+        .map(|rel_dir_path| {
+            generate_index_file_for_dir(args, site_map, metadata_map, html_template, &json_regex, rel_dir_path)
+        })
+        .collect();
+
+    if let Some(Err(e)) = results.into_iter().find(|r| r.is_err()) {
+        return Err(e);
+    }
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::{fs, io, path::Path, thread};
+
+use tiny_http::{Header, ListenAddr, Response, Server};
+
+use crate::config::Args;
+use crate::io::{print_error, print_info};
+use crate::watch::watch_and_rebuild;
+
+/// Builds the site once (the caller does the initial `process_directory` /
+/// `generate_all_index_files` pass before calling this, same as every other
+/// output path), then serves `args.target` as plain static files while a
+/// background thread runs the existing `watch_and_rebuild` loop. Unlike
+/// `serve::run_serve`'s in-memory render path (no files ever touch disk),
+/// this one's rebuilds go through the real disk-writing pipeline and its
+/// unchanged-file skip logic, so what a browser fetches is exactly what a
+/// one-shot run would have produced.
+pub fn run_preview(args: Args, html_template: String, port: u16) -> io::Result<()> {
+    let server = Server::http(format!("0.0.0.0:{}", port))
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    let actual_port = match server.server_addr() {
+        ListenAddr::IP(addr) => addr.port(),
+        _ => port,
+    };
+
+    print_info(&format!(
+        "Previewing {} at http://localhost:{}/ (Ctrl+C to stop)",
+        args.target.display(),
+        actual_port
+    ));
+
+    let watch_args = args.clone();
+    thread::spawn(move || {
+        if let Err(e) = watch_and_rebuild(&watch_args, &html_template) {
+            print_error(&format!("Preview watcher stopped: {}", e));
+        }
+    });
+
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        let response = serve_static_file(&args.target, &url);
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+fn serve_static_file(target_root: &Path, url: &str) -> Response<io::Cursor<Vec<u8>>> {
+    let requested_path = url.split('?').next().unwrap_or("/");
+    let relative = requested_path.trim_start_matches('/');
+
+    // A raw `target_root.join(relative)` lets a `..` component in the
+    // request path walk back out of `target_root` and read arbitrary files
+    // reachable from the process's permissions (e.g. `GET /../../etc/passwd`).
+    // Reject any such request before it ever reaches `fs::read`.
+    if Path::new(relative)
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Response::from_string("404 Not Found").with_status_code(404);
+    }
+
+    let mut candidate = target_root.join(relative);
+    if relative.is_empty() || candidate.is_dir() {
+        candidate = candidate.join("index.html");
+    }
+
+    match fs::read(&candidate) {
+        Ok(bytes) => {
+            let header =
+                Header::from_bytes(&b"Content-Type"[..], content_type_for(&candidate).as_bytes())
+                    .unwrap();
+            Response::from_data(bytes).with_header(header)
+        }
+        Err(_) => Response::from_string("404 Not Found").with_status_code(404),
+    }
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
@@ -1,18 +1,27 @@
 use chrono::{DateTime, Utc};
 use pulldown_cmark::{Options, Parser};
+use rayon::prelude::*;
 use regex::Regex;
-use std::{collections::BTreeMap, fs, io, path::Path};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
+use crate::backlinks::{render_backlinks_html, BacklinksIndex};
 use crate::config::{Args, MetadataMap, PageMetadata, SiteMap};
 use crate::html::{
     convert_urls_to_anchors, format_html_page, generate_breadcrumb_html, generate_canonical_url,
+    minify_html, KATEX_HEAD_INCLUDE, MERMAID_HEAD_INCLUDE,
 };
-use crate::io::{print_error, print_info, print_warning};
+use crate::io::{collect_all_dirs_robust, print_error, print_info, print_warning};
 use crate::markdown::{
-    check_broken_links, normalize_markdown_content, prepare_content_for_parser,
-    process_markdown_events,
+    check_broken_links, extract_plain_text, normalize_markdown_content,
+    prepare_content_for_parser, process_markdown_events,
 };
-use crate::nav::generate_navigation_html;
+use crate::nav::{generate_navigation_html, render_prev_next_html};
+use crate::search::SearchIndexBuilder;
 
 pub fn load_all_metadata_from_files(args: &Args, site_map: &SiteMap) -> io::Result<MetadataMap> {
     let mut metadata_map = BTreeMap::new();
@@ -25,8 +34,34 @@ pub fn load_all_metadata_from_files(args: &Args, site_map: &SiteMap) -> io::Resu
         let path_source = args.source.join(rel_path);
         let markdown_input = fs::read_to_string(&path_source)?;
         let mut metadata = PageMetadata::default();
+        let mut content_for_title = markdown_input.clone();
 
-        if let Some(caps) = json_regex.captures(&markdown_input) {
+        if let Some((front_matter, remainder)) = split_front_matter(&markdown_input, "+++") {
+            match toml::from_str::<PageMetadata>(front_matter) {
+                Ok(parsed_meta) => {
+                    metadata = parsed_meta;
+                    content_for_title = remainder.to_string();
+                }
+                Err(e) => print_error(&format!(
+                    "Failed to parse TOML front matter in {}: {}",
+                    rel_path.display(),
+                    e
+                )),
+            }
+        } else if let Some((front_matter, remainder)) = split_front_matter(&markdown_input, "---")
+        {
+            match serde_yaml::from_str::<PageMetadata>(front_matter) {
+                Ok(parsed_meta) => {
+                    metadata = parsed_meta;
+                    content_for_title = remainder.to_string();
+                }
+                Err(e) => print_error(&format!(
+                    "Failed to parse YAML front matter in {}: {}",
+                    rel_path.display(),
+                    e
+                )),
+            }
+        } else if let Some(caps) = json_regex.captures(&markdown_input) {
             let json_str = &caps[1];
             match serde_json::from_str::<PageMetadata>(json_str) {
                 Ok(parsed_meta) => metadata = parsed_meta,
@@ -40,7 +75,7 @@ pub fn load_all_metadata_from_files(args: &Args, site_map: &SiteMap) -> io::Resu
 
         let computed_title = {
             let content_without_json = json_regex
-                .replace_all(&markdown_input, |caps: &regex::Captures| {
+                .replace_all(&content_for_title, |caps: &regex::Captures| {
                     caps.get(2).map_or("", |m| m.as_str()).to_string()
                 })
                 .to_string();
@@ -86,12 +121,65 @@ pub fn load_all_metadata_from_files(args: &Args, site_map: &SiteMap) -> io::Resu
     Ok(metadata_map)
 }
 
-pub fn process_directory(
+/// Splits off a leading fenced front-matter block delimited by a repeated
+/// `delimiter` line (`+++` for TOML, `---` for YAML), returning the block's
+/// body and the remaining markdown. Returns `None` when the file doesn't
+/// open with that exact delimiter on its own line.
+fn split_front_matter<'a>(content: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let mut lines = content.lines();
+    if lines.next()?.trim_end() != delimiter {
+        return None;
+    }
+
+    let after_open = content.find('\n').map(|i| i + 1).unwrap_or(content.len());
+    let rest = &content[after_open..];
+
+    let mut offset = 0usize;
+    while offset < rest.len() {
+        // Find this line's real end by byte position rather than assuming a
+        // single-byte `\n` terminator: `str::lines()` strips `\r\n` as well
+        // as `\n`, so reconstructing the next line's start from `line.len()
+        // + 1` undercounts by one byte per CRLF line and drifts `offset` out
+        // of sync with `rest`'s real byte positions.
+        let line_end = rest[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(rest.len());
+        let line = &rest[offset..line_end];
+
+        if line.trim_end_matches(['\r', '\n']) == delimiter {
+            let front_matter = &rest[..offset];
+            let remainder_start = (line_end + 1).min(rest.len());
+            return Some((front_matter, &rest[remainder_start..]));
+        }
+
+        offset = (line_end + 1).min(rest.len());
+        if line_end == rest.len() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// The set of source files under one `process_directory` walk, split by how
+/// they're handled, so the two groups can be driven through `rayon`
+/// independently once the (cheap, sequential) directory walk is done.
+#[derive(Debug, Default)]
+struct WorkItems {
+    markdown_files: Vec<PathBuf>,
+    copy_files: Vec<PathBuf>,
+}
+
+/// Walks `current_dir_source`, creating the mirrored target directory
+/// structure and sorting files into `WorkItems` by how they're handled.
+/// Directory-level `avoid_generation`/exclusion rules are applied here, up
+/// front, so the later parallel passes don't need to re-check them.
+fn collect_work_items(
     args: &Args,
-    site_map: &SiteMap,
     metadata_map: &MetadataMap,
     current_dir_source: &Path,
-    html_template: &str,
+    items: &mut WorkItems,
 ) -> io::Result<()> {
     let current_dir_rel = current_dir_source
         .strip_prefix(&args.source)
@@ -131,10 +219,9 @@ pub fn process_directory(
                 }
             }
 
-            process_directory(args, site_map, metadata_map, &path_source, html_template)?;
+            collect_work_items(args, metadata_map, &path_source, items)?;
         } else if path_source.is_file() {
             let file_name = path_source.file_name().unwrap_or_default();
-            let path_target = current_dir_target.join(file_name);
 
             let rel_path = path_source
                 .strip_prefix(&args.source)
@@ -187,24 +274,122 @@ pub fn process_directory(
                     continue;
                 }
 
-                markdown_to_html(
-                    args,
-                    site_map,
-                    metadata,
-                    &path_source,
-                    &path_target,
-                    rel_path,
-                    html_template,
-                    metadata_map,
-                )?;
+                items.markdown_files.push(rel_path.to_path_buf());
             } else {
-                smart_copy_file(args, &path_source, &path_target, rel_path)?;
+                items.copy_files.push(rel_path.to_path_buf());
             }
         }
     }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+fn process_one_markdown_file(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    all_dirs: &HashSet<PathBuf>,
+    backlinks_index: &BacklinksIndex,
+    html_template: &str,
+    rel_path: &Path,
+    search_index: Option<&Mutex<SearchIndexBuilder>>,
+) -> io::Result<()> {
+    let path_source = args.source.join(rel_path);
+    let path_target = args.target.join(rel_path);
+    let metadata = metadata_map
+        .get(rel_path)
+        .expect("Metadata should exist for every markdown file in site_map");
+
+    markdown_to_html(
+        args,
+        site_map,
+        metadata,
+        all_dirs,
+        &path_source,
+        &path_target,
+        rel_path,
+        html_template,
+        metadata_map,
+        backlinks_index,
+        search_index,
+    )
+}
+
+fn process_one_copy_file(args: &Args, rel_path: &Path) -> io::Result<()> {
+    let path_source = args.source.join(rel_path);
+    let file_name = path_source.file_name().unwrap_or_default();
+    let current_dir_target = args
+        .target
+        .join(rel_path.parent().unwrap_or(Path::new("")));
+    let path_target = current_dir_target.join(file_name);
+
+    smart_copy_file(args, &path_source, &path_target, rel_path)
+}
+
+/// Walks `current_dir_source` (mirroring its directory structure into
+/// `args.target`), then renders every markdown file and copies every other
+/// file, each group driven through `rayon::par_iter` since one file's
+/// output is independent of another's (`nav_html`, markdown parsing, the
+/// unchanged-content skip check, and the final `fs::write`/`fs::copy` don't
+/// touch shared state). Errors are reduced back to the first markdown error
+/// in source order, or failing that the first copy error, so `verbose` logging and the process exit
+/// code stay deterministic regardless of which thread finishes first.
+#[allow(clippy::too_many_arguments)]
+pub fn process_directory(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    backlinks_index: &BacklinksIndex,
+    current_dir_source: &Path,
+    html_template: &str,
+    search_index: &mut Option<SearchIndexBuilder>,
+) -> io::Result<()> {
+    let mut items = WorkItems::default();
+    collect_work_items(args, metadata_map, current_dir_source, &mut items)?;
+
+    // Every directory under `args.source` gets an index page (even ones with
+    // no `index.md` of their own), so a link to a directory is only really
+    // "broken" if it's absent from this set, not from `site_map` (which only
+    // tracks real files).
+    let all_dirs = collect_all_dirs_robust(&args.source)?;
+
+    let search_index_mutex = search_index.take().map(Mutex::new);
+
+    let markdown_results: Vec<io::Result<()>> = items
+        .markdown_files
+        .par_iter()
+        .map(|rel_path| {
+            process_one_markdown_file(
+                args,
+                site_map,
+                metadata_map,
+                &all_dirs,
+                backlinks_index,
+                html_template,
+                rel_path,
+                search_index_mutex.as_ref(),
+            )
+        })
+        .collect();
+
+    *search_index = search_index_mutex.map(|mutex| mutex.into_inner().unwrap());
+
+    let copy_results: Vec<io::Result<()>> = items
+        .copy_files
+        .par_iter()
+        .map(|rel_path| process_one_copy_file(args, rel_path))
+        .collect();
+
+    if let Some(Err(e)) = markdown_results.into_iter().find(|r| r.is_err()) {
+        return Err(e);
+    }
+    if let Some(Err(e)) = copy_results.into_iter().find(|r| r.is_err()) {
+        return Err(e);
+    }
+
+    Ok(())
+}
+
 pub fn smart_copy_file(
     args: &Args,
     path_source: &Path,
@@ -273,14 +458,14 @@ fn read_and_normalize_markdown(
     Ok(normalized_content)
 }
 
-fn parse_markdown_to_html(
+pub(crate) fn parse_markdown_to_html(
     content: &str,
     metadata: &PageMetadata,
     args: &Args,
     site_map: &SiteMap,
     metadata_map: &MetadataMap,
     path_rel: &Path,
-) -> (String, String) {
+) -> (String, String, String, bool, bool) {
     let content_for_parser = prepare_content_for_parser(content, metadata);
 
     let mut options = Options::empty();
@@ -290,18 +475,36 @@ fn parse_markdown_to_html(
     options.insert(Options::ENABLE_FOOTNOTES);
 
     let parser = Parser::new_ext(&content_for_parser, options);
-    process_markdown_events(args, site_map, metadata_map, parser, path_rel)
+    let (content_html, title, toc_html, uses_math, uses_mermaid) =
+        process_markdown_events(args, site_map, metadata_map, parser, path_rel);
+
+    let toc_html = if metadata.disable_toc.unwrap_or(false) {
+        String::new()
+    } else {
+        toc_html
+    };
+
+    let uses_math = uses_math && !metadata.disable_math.unwrap_or(false);
+    let uses_mermaid = uses_mermaid && !metadata.disable_mermaid.unwrap_or(false);
+
+    (content_html, title, toc_html, uses_math, uses_mermaid)
 }
 
-fn build_final_html(
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_final_html(
     title: &str,
     path_rel: &Path,
     path_source: &Path,
     nav_html: &str,
     content: &str,
+    toc_html: &str,
+    uses_math: bool,
+    uses_mermaid: bool,
     html_template: &str,
     args: &Args,
+    site_map: &SiteMap,
     metadata_map: &MetadataMap,
+    backlinks_index: &BacklinksIndex,
 ) -> String {
     let date_created = get_creation_date(path_source);
     let last_modified_time = get_last_modified_date(path_source);
@@ -319,18 +522,38 @@ fn build_final_html(
 
     let breadcrumb_html = generate_breadcrumb_html(path_rel, metadata_map, &args.base_url);
     let canonical_url = generate_canonical_url(path_rel, &args.base_url);
+    let backlinks_html = if args.enable_backlinks {
+        render_backlinks_html(path_rel, backlinks_index, metadata_map, &args.base_url)
+    } else {
+        String::new()
+    };
+    let prev_next_html = render_prev_next_html(args, site_map, metadata_map, path_rel);
+
+    let math_assets_html = if uses_math { KATEX_HEAD_INCLUDE } else { "" };
+    let mermaid_assets_html = if uses_mermaid { MERMAID_HEAD_INCLUDE } else { "" };
 
-    format_html_page(
+    let rendered_page = format_html_page(
         title,
         &rel_path_str,
         &date_created,
         &last_modified_time,
         nav_html,
         &final_content,
+        toc_html,
+        &backlinks_html,
+        &prev_next_html,
+        math_assets_html,
+        mermaid_assets_html,
         html_template,
         &breadcrumb_html,
         &canonical_url,
-    )
+    );
+
+    if args.minify_html {
+        minify_html(&rendered_page)
+    } else {
+        rendered_page
+    }
 }
 
 fn should_skip_html_write(
@@ -368,28 +591,33 @@ fn should_skip_html_write(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn markdown_to_html(
     args: &Args,
     site_map: &SiteMap,
     metadata: &PageMetadata,
+    all_dirs: &HashSet<PathBuf>,
     path_source: &Path,
     path_target: &Path,
     path_rel: &Path,
     html_template: &str,
     metadata_map: &MetadataMap,
+    backlinks_index: &BacklinksIndex,
+    search_index: Option<&Mutex<SearchIndexBuilder>>,
 ) -> io::Result<()> {
     let normalized_content = read_and_normalize_markdown(path_source, path_rel, args)?;
 
-    check_broken_links(&normalized_content, path_source, path_rel);
+    check_broken_links(&normalized_content, path_rel, site_map, all_dirs, args);
 
-    let (html_output_content, title_from_h1) = parse_markdown_to_html(
-        &normalized_content,
-        metadata,
-        args,
-        site_map,
-        metadata_map,
-        path_rel,
-    );
+    let (html_output_content, title_from_h1, toc_html, uses_math, uses_mermaid) =
+        parse_markdown_to_html(
+            &normalized_content,
+            metadata,
+            args,
+            site_map,
+            metadata_map,
+            path_rel,
+        );
 
     let title = metadata
         .page_title
@@ -399,15 +627,36 @@ pub fn markdown_to_html(
 
     let nav_html = generate_navigation_html(args, site_map, metadata_map, path_rel);
 
+    if args.enable_search_index && metadata.include_in_search.unwrap_or(true) {
+        if let Some(mutex) = search_index {
+            let plain_text = extract_plain_text(&normalized_content);
+            if !plain_text.trim().is_empty() {
+                let canonical_url = generate_canonical_url(path_rel, &args.base_url);
+                let computed_title = metadata.computed_title.as_ref().unwrap_or(&title);
+                mutex.lock().unwrap().add_page(
+                    computed_title,
+                    &canonical_url,
+                    &plain_text,
+                    args.search_index_max_body_len,
+                );
+            }
+        }
+    }
+
     let final_html = build_final_html(
         &title,
         path_rel,
         path_source,
         &nav_html,
         &html_output_content,
+        &toc_html,
+        uses_math,
+        uses_mermaid,
         html_template,
         args,
+        site_map,
         metadata_map,
+        backlinks_index,
     );
 
     let mut path_target_html = path_target.to_path_buf();
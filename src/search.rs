@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::config::Args;
+use crate::io::print_info;
+
+// A short stopword list; always filtered out of titles and bodies so common words don't dominate postings.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "of", "to", "in", "on", "for", "is", "are", "was",
+    "were", "be", "been", "it", "its", "this", "that", "with", "as", "at", "by", "from",
+];
+
+#[derive(Debug, Serialize)]
+pub struct SearchDoc {
+    pub id: usize,
+    pub title: String,
+    pub url: String,
+    pub body: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchIndex {
+    pub fields: Vec<String>,
+    pub documents: BTreeMap<usize, SearchDoc>,
+    pub index: BTreeMap<String, BTreeMap<usize, usize>>,
+}
+
+/// Accumulates one entry per page as the tree is walked, then serializes a
+/// single elasticlunr-style `search_index.json` at the target root.
+#[derive(Debug, Default)]
+pub struct SearchIndexBuilder {
+    next_id: usize,
+    index: SearchIndex,
+}
+
+impl SearchIndexBuilder {
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            index: SearchIndex {
+                fields: vec!["title".to_string(), "body".to_string()],
+                ..SearchIndex::default()
+            },
+        }
+    }
+
+    pub fn add_page(&mut self, title: &str, url: &str, body: &str, max_body_len: Option<usize>) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let truncated_body = match max_body_len {
+            Some(max) if body.chars().count() > max => body.chars().take(max).collect(),
+            _ => body.to_string(),
+        };
+
+        for term in tokenize(title).chain(tokenize(&truncated_body)) {
+            *self.index.index.entry(term).or_default().entry(id).or_insert(0) += 1;
+        }
+
+        self.index.documents.insert(
+            id,
+            SearchDoc {
+                id,
+                title: title.to_string(),
+                url: url.to_string(),
+                body: truncated_body,
+            },
+        );
+    }
+
+    pub fn write(&self, args: &Args) -> io::Result<()> {
+        let path = args.target.join("search_index.json");
+        let json = serde_json::to_string(&self.index)?;
+        std::fs::write(&path, json)?;
+
+        if args.verbose {
+            print_info(&format!("Wrote search index to: {}", path.display()));
+        }
+
+        Ok(())
+    }
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .filter(|s| !STOPWORDS.contains(&s.as_str()))
+}
@@ -0,0 +1,273 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::channel,
+        Arc, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use notify::{RecursiveMode, Watcher};
+use tiny_http::{Header, Response, Server};
+
+use crate::backlinks::{build_backlinks_index, BacklinksIndex};
+use crate::config::{Args, MetadataMap, PageMetadata, SiteMap};
+use crate::html::generate_canonical_url;
+use crate::io::{print_error, print_info};
+use crate::markdown::normalize_markdown_content;
+use crate::nav::generate_navigation_html;
+use crate::processing::{build_final_html, load_all_metadata_from_files, parse_markdown_to_html};
+use crate::site_map::build_site_map;
+
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+(function() {
+  var lastVersion = null;
+  setInterval(function() {
+    fetch('/__reload_version').then(function(r) { return r.text(); }).then(function(v) {
+      if (lastVersion === null) { lastVersion = v; return; }
+      if (v !== lastVersion) { location.reload(); }
+    }).catch(function() {});
+  }, 1000);
+})();
+</script>"#;
+
+/// Renders every markdown page into an in-memory `site-relative canonical
+/// URL -> HTML` map and serves it over HTTP without ever touching disk. Only
+/// those pages are served: other source files (images, stylesheets) and the
+/// synthesized directory and taxonomy index pages answer 404. A background thread
+/// watches the source tree and rebuilds the map on change; a small polling
+/// script (good enough for a local dev loop, and simpler than wiring up a
+/// WebSocket/SSE endpoint) tells open tabs to reload once the version
+/// counter it bumps has moved.
+pub fn run_serve(
+    args: Args,
+    site_map: SiteMap,
+    metadata_map: MetadataMap,
+    html_template: String,
+    port: u16,
+) -> io::Result<()> {
+    let backlinks_index = if args.enable_backlinks {
+        build_backlinks_index(&args, &site_map)
+    } else {
+        BacklinksIndex::default()
+    };
+
+    let pages = Arc::new(RwLock::new(build_page_map(
+        &args,
+        &site_map,
+        &metadata_map,
+        &backlinks_index,
+        &html_template,
+    )?));
+    let version = Arc::new(AtomicU64::new(0));
+
+    spawn_rebuild_watcher(args.clone(), html_template.clone(), Arc::clone(&pages), Arc::clone(&version));
+
+    let server = Server::http(format!("0.0.0.0:{}", port))
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    print_info(&format!(
+        "Serving in-memory site at http://localhost:{}/ (Ctrl+C to stop)",
+        port
+    ));
+
+    for request in server.incoming_requests() {
+        let requested_path = request.url().split('?').next().unwrap_or("/").to_string();
+
+        if requested_path == "/__reload_version" {
+            let body = version.load(Ordering::Relaxed).to_string();
+            let _ = request.respond(Response::from_string(body));
+            continue;
+        }
+
+        let pages_guard = pages.read().unwrap();
+        match pages_guard.get(&requested_path) {
+            Some(html) => {
+                let header =
+                    Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+                        .unwrap();
+                let _ = request.respond(Response::from_string(html.clone()).with_header(header));
+            }
+            None => {
+                let _ = request.respond(
+                    Response::from_string("404 Not Found").with_status_code(404),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn spawn_rebuild_watcher(
+    args: Args,
+    html_template: String,
+    pages: Arc<RwLock<HashMap<String, String>>>,
+    version: Arc<AtomicU64>,
+) {
+    thread::spawn(move || {
+        let (tx, rx) = channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                print_error(&format!("Failed to start serve-mode file watcher: {}", e));
+                return;
+            }
+        };
+
+        if let Err(e) = watcher.watch(&args.source, RecursiveMode::Recursive) {
+            print_error(&format!(
+                "Failed to watch {}: {}",
+                args.source.display(),
+                e
+            ));
+            return;
+        }
+
+        loop {
+            let first_event = match rx.recv() {
+                Ok(event) => event,
+                Err(_) => break,
+            };
+
+            let mut events = vec![first_event];
+            while let Ok(event) = rx.recv_timeout(Duration::from_millis(300)) {
+                events.push(event);
+            }
+
+            if events.iter().all(Result::is_err) {
+                continue;
+            }
+
+            // Full reload rather than per-file patching: nav/breadcrumbs depend
+            // on the whole metadata map, so a partial rebuild risks staleness
+            // for the sake of speed we don't need in a local dev loop.
+            let rebuilt = build_site_map(&args.source).and_then(|new_site_map| {
+                let new_metadata_map = load_all_metadata_from_files(&args, &new_site_map)?;
+                let new_backlinks_index = if args.enable_backlinks {
+                    build_backlinks_index(&args, &new_site_map)
+                } else {
+                    BacklinksIndex::default()
+                };
+                let new_pages = build_page_map(
+                    &args,
+                    &new_site_map,
+                    &new_metadata_map,
+                    &new_backlinks_index,
+                    &html_template,
+                )?;
+                Ok(new_pages)
+            });
+
+            match rebuilt {
+                Ok(new_pages) => {
+                    *pages.write().unwrap() = new_pages;
+                    version.fetch_add(1, Ordering::Relaxed);
+                    print_info("Rebuilt in-memory site after source change.");
+                }
+                Err(e) => print_error(&format!("Serve-mode rebuild failed: {}", e)),
+            }
+        }
+    });
+}
+
+fn build_page_map(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    backlinks_index: &BacklinksIndex,
+    html_template: &str,
+) -> io::Result<HashMap<String, String>> {
+    let mut pages = HashMap::new();
+
+    let mut md_paths: Vec<_> = site_map
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    md_paths.sort();
+
+    for rel_path in md_paths {
+        let should_skip = metadata_map
+            .get(rel_path)
+            .and_then(|m| m.avoid_generation)
+            .unwrap_or(false);
+        if should_skip {
+            continue;
+        }
+
+        let (canonical_url, html) = render_page(
+            args,
+            site_map,
+            metadata_map,
+            backlinks_index,
+            html_template,
+            rel_path,
+        )?;
+        pages.insert(canonical_url, html);
+    }
+
+    Ok(pages)
+}
+
+fn render_page(
+    args: &Args,
+    site_map: &SiteMap,
+    metadata_map: &MetadataMap,
+    backlinks_index: &BacklinksIndex,
+    html_template: &str,
+    rel_path: &Path,
+) -> io::Result<(String, String)> {
+    let path_source = args.source.join(rel_path);
+    let markdown_input = fs::read_to_string(&path_source)?;
+    let (normalized_content, _) = normalize_markdown_content(&markdown_input, &path_source);
+
+    let default_metadata = PageMetadata::default();
+    let metadata = metadata_map.get(rel_path).unwrap_or(&default_metadata);
+
+    let (content_html, title_from_h1, toc_html, uses_math, uses_mermaid) =
+        parse_markdown_to_html(&normalized_content, metadata, args, site_map, metadata_map, rel_path);
+
+    let title = metadata
+        .page_title
+        .as_ref()
+        .unwrap_or(&title_from_h1)
+        .clone();
+
+    let nav_html = generate_navigation_html(args, site_map, metadata_map, rel_path);
+
+    let final_html = build_final_html(
+        &title,
+        rel_path,
+        &path_source,
+        &nav_html,
+        &content_html,
+        &toc_html,
+        uses_math,
+        uses_mermaid,
+        html_template,
+        args,
+        site_map,
+        metadata_map,
+        backlinks_index,
+    );
+
+    let final_html = inject_live_reload_script(&final_html);
+    // Keyed without `--base-url` so the map matches the bare request path.
+    let canonical_url = generate_canonical_url(rel_path, "");
+
+    Ok((canonical_url, final_html))
+}
+
+fn inject_live_reload_script(html: &str) -> String {
+    if let Some(idx) = html.rfind("</body>") {
+        let mut out = html.to_string();
+        out.insert_str(idx, LIVE_RELOAD_SCRIPT);
+        out
+    } else {
+        format!("{}{}", html, LIVE_RELOAD_SCRIPT)
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::{Args, MetadataMap};
+use crate::html::{format_html_page, generate_canonical_url};
+use crate::io::print_info;
+
+/// `taxonomy name -> term -> pages carrying that term`, built from
+/// `PageMetadata::taxonomies` (plus the `tags` shorthand, folded into the
+/// same `"tags"` bucket) after all page metadata has been loaded.
+pub type TaxonomyIndex = BTreeMap<String, BTreeMap<String, Vec<PathBuf>>>;
+
+pub(crate) const TAGS_TAXONOMY: &str = "tags";
+
+pub fn build_taxonomy_index(metadata_map: &MetadataMap) -> TaxonomyIndex {
+    let mut taxonomy_index: TaxonomyIndex = BTreeMap::new();
+
+    for (rel_path, metadata) in metadata_map.iter() {
+        if let Some(taxonomies) = &metadata.taxonomies {
+            for (taxonomy_name, terms) in taxonomies {
+                for term in terms {
+                    taxonomy_index
+                        .entry(taxonomy_name.clone())
+                        .or_default()
+                        .entry(term.clone())
+                        .or_default()
+                        .push(rel_path.clone());
+                }
+            }
+        }
+
+        if let Some(tags) = &metadata.tags {
+            for tag in tags {
+                let pages = taxonomy_index
+                    .entry(TAGS_TAXONOMY.to_string())
+                    .or_default()
+                    .entry(tag.clone())
+                    .or_default();
+                if !pages.contains(rel_path) {
+                    pages.push(rel_path.clone());
+                }
+            }
+        }
+    }
+
+    taxonomy_index
+}
+
+/// Synthesizes `/<taxonomy>/<slug>/index.html` and `/<taxonomy>/index.html`
+/// listing pages from the in-memory taxonomy index: these have no source
+/// `.md` file, so they're rendered directly rather than via `fs::read_dir`.
+pub fn generate_taxonomy_pages(
+    args: &Args,
+    metadata_map: &MetadataMap,
+    taxonomy_index: &TaxonomyIndex,
+    html_template: &str,
+) -> io::Result<()> {
+    for (taxonomy_name, terms) in taxonomy_index {
+        let taxonomy_dir = args.target.join(taxonomy_name);
+        fs::create_dir_all(&taxonomy_dir)?;
+
+        let mut overview_items = String::new();
+
+        for (term, pages) in terms {
+            let slug = slugify_term(term);
+            let term_dir = taxonomy_dir.join(&slug);
+            fs::create_dir_all(&term_dir)?;
+
+            let mut sorted_pages: Vec<&PathBuf> = pages.iter().collect();
+            sorted_pages.sort_by_key(|p| page_title(metadata_map, p));
+
+            let mut list_html = String::from("<ul>");
+            for page in &sorted_pages {
+                let title = page_title(metadata_map, page);
+                let url = generate_canonical_url(page, &args.base_url);
+                list_html.push_str(&format!(r#"<li><a href="{}">{}</a></li>"#, url, title));
+            }
+            list_html.push_str("</ul>");
+
+            let term_rel_path = PathBuf::from(taxonomy_name).join(&slug).join("index.md");
+            let canonical_url = generate_canonical_url(&term_rel_path, &args.base_url);
+            let page_title_str = format!("{}: {}", titlecase(taxonomy_name), term);
+
+            let final_html = format_html_page(
+                &page_title_str,
+                &format!("/{}/{}/", taxonomy_name, slug),
+                "N/A",
+                "N/A",
+                "",
+                &list_html,
+                "",
+                "",
+                "",
+                "",
+                "",
+                html_template,
+                "",
+                &canonical_url,
+            );
+
+            fs::write(term_dir.join("index.html"), final_html)?;
+
+            overview_items.push_str(&format!(
+                r#"<li><a href="/{}/{}/">{}</a> ({})</li>"#,
+                taxonomy_name,
+                slug,
+                term,
+                pages.len()
+            ));
+        }
+
+        let overview_rel_path = PathBuf::from(taxonomy_name).join("index.md");
+        let overview_canonical_url = generate_canonical_url(&overview_rel_path, &args.base_url);
+        let overview_title = titlecase(taxonomy_name);
+        let overview_html = format!("<ul>{}</ul>", overview_items);
+
+        let overview_final_html = format_html_page(
+            &overview_title,
+            &format!("/{}/", taxonomy_name),
+            "N/A",
+            "N/A",
+            "",
+            &overview_html,
+            "",
+            "",
+            "",
+            "",
+            "",
+            html_template,
+            "",
+            &overview_canonical_url,
+        );
+
+        fs::write(taxonomy_dir.join("index.html"), overview_final_html)?;
+
+        if args.verbose {
+            print_info(&format!(
+                "Generated taxonomy listing pages for: {}",
+                taxonomy_name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn page_title(metadata_map: &MetadataMap, rel_path: &PathBuf) -> String {
+    metadata_map
+        .get(rel_path)
+        .and_then(|m| m.nav_title.clone().or_else(|| m.computed_title.clone()))
+        .unwrap_or_else(|| rel_path.to_string_lossy().to_string())
+}
+
+pub(crate) fn slugify_term(term: &str) -> String {
+    let mut slug = String::with_capacity(term.len());
+    let mut last_was_hyphen = true;
+
+    for ch in term.to_lowercase().chars() {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+            last_was_hyphen = false;
+        } else if !last_was_hyphen {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
+
+fn titlecase(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,130 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::config::Args;
+use crate::io::{print_error, print_info};
+use crate::nav::generate_all_index_files;
+use crate::backlinks::{build_backlinks_index, BacklinksIndex};
+use crate::html::generate_sitemap_xml;
+use crate::markdown::print_link_check_summary;
+use crate::processing::{load_all_metadata_from_files, process_directory};
+use crate::search::SearchIndexBuilder;
+use crate::site_map::build_site_map;
+use crate::taxonomy::{build_taxonomy_index, generate_taxonomy_pages};
+
+// Collapses a burst of filesystem events (e.g. an editor's save-then-touch)
+// into a single rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches `args.source` with `notify` and rebuilds on change after the
+/// initial full build has already run.
+pub fn watch_and_rebuild(args: &Args, html_template: &str) -> io::Result<()> {
+    let (tx, rx) = channel();
+
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })
+    .map_err(to_io_error)?;
+
+    watcher
+        .watch(&args.source, RecursiveMode::Recursive)
+        .map_err(to_io_error)?;
+
+    print_info(&format!(
+        "Watching {} for changes (Ctrl+C to stop)...",
+        args.source.display()
+    ));
+
+    loop {
+        let first_event = match rx.recv() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+
+        let mut events = vec![first_event];
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+
+        let changed_paths: Vec<PathBuf> = events
+            .into_iter()
+            .filter_map(|res| res.ok())
+            .flat_map(|event| event.paths)
+            .collect();
+
+        if changed_paths.is_empty() {
+            continue;
+        }
+
+        if args.verbose {
+            for path in &changed_paths {
+                print_info(&format!("Change detected: {}", path.display()));
+            }
+        }
+
+        match rebuild(args, html_template) {
+            Ok(()) => print_info("Rebuild complete."),
+            Err(e) => print_error(&format!("Rebuild failed: {}", e)),
+        }
+    }
+
+    Ok(())
+}
+
+// Titles and `avoid_generation` flags feed navigation and breadcrumbs
+// globally, so a single changed file can change every page's nav. Rather
+// than patch the in-memory `SiteMap`/`MetadataMap` for just the affected
+// path, reload both and re-walk the tree; `should_skip_html_write` and
+// `smart_copy_file`'s content comparison keep unaffected pages from being
+// rewritten, so this stays cheap in practice. Mirrors every stage of
+// `main()`'s one-shot build (search index, taxonomy pages, sitemap, link
+// check summary), not just the directory walk, so a tree built with
+// `--search-index`/tags/taxonomies/`--check-links` doesn't go stale after
+// the first rebuild under `--watch`/`preview`.
+fn rebuild(args: &Args, html_template: &str) -> io::Result<()> {
+    let site_map = build_site_map(&args.source)?;
+    let metadata_map = load_all_metadata_from_files(args, &site_map)?;
+    let mut search_index = if args.enable_search_index {
+        Some(SearchIndexBuilder::new())
+    } else {
+        None
+    };
+    let backlinks_index = if args.enable_backlinks {
+        build_backlinks_index(args, &site_map)
+    } else {
+        BacklinksIndex::default()
+    };
+
+    process_directory(
+        args,
+        &site_map,
+        &metadata_map,
+        &backlinks_index,
+        &args.source,
+        html_template,
+        &mut search_index,
+    )?;
+
+    generate_all_index_files(args, &site_map, &metadata_map, html_template)?;
+
+    generate_sitemap_xml(args, &metadata_map)?;
+
+    print_link_check_summary(args);
+
+    let taxonomy_index = build_taxonomy_index(&metadata_map);
+    generate_taxonomy_pages(args, &metadata_map, &taxonomy_index, html_template)?;
+
+    if let Some(builder) = search_index {
+        builder.write(args)?;
+    }
+
+    Ok(())
+}
+
+fn to_io_error(e: notify::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e.to_string())
+}